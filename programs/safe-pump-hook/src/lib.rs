@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use safe_pump::cpi::accounts::RecordHookSell;
+use safe_pump::program::SafePump;
+use safe_pump::{LaunchConfig, TokenContract};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_token_2022::extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("HBmPxx1zxw7vLtaFmMHBUZPeAyCh6iZjnt59pxitwHSR");
+
+// Execute account indices: 0 source, 1 mint, 2 destination, 3 owner, 4 extra-account-metas
+const EXTRA_ACCOUNT_COUNT: usize = 6;
+
+#[program]
+pub mod safe_pump_hook {
+    use super::*;
+
+    // Anyone may publish the extra-account-metas for a SafePump mint, they only depend on its contract
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        let metas = extra_account_metas(&ctx.accounts.contract.key(), &ctx.accounts.contract.launch_config)?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &metas,
+        )?;
+        msg!("Initialized transfer hook accounts for mint {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        assert_is_transferring(&ctx.accounts.source_token)?;
+
+        // Tokens leaving the holder's wallet count against the sell limits, whichever pool or account receives them
+        let contract = &ctx.accounts.contract;
+        if ctx.accounts.destination_token.owner == ctx.accounts.source_token.owner {
+            return Ok(());
+        }
+        // Protocol transfers out of contract-owned accounts are not sells
+        if ctx.accounts.owner.key() == contract.key() {
+            return Ok(());
+        }
+        // Buys out of the bonded pool are paid by the pool authority
+        if ctx.accounts.source_token.key() == contract.pool_vault {
+            return Ok(());
+        }
+        // Deposits into contract vaults are priced by the safe_pump instruction moving them
+        if ctx.accounts.destination_token.owner == contract.key() {
+            return Ok(());
+        }
+        // The swap instructions enforce the same rules themselves, and safe_pump cannot be re-entered from here
+        let current_instruction =
+            instructions_sysvar::get_instruction_relative(0, &ctx.accounts.instructions_sysvar.to_account_info())?;
+        if current_instruction.program_id == safe_pump::ID && is_checked_swap(&current_instruction.data) {
+            return Ok(());
+        }
+
+        // Delegates would resolve someone else's UserSwapData
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.source_token.owner,
+            HookError::DelegatedSell
+        );
+        // Token-2022 drops signer privileges for the hook, so nobody can pay to create the PDA here
+        require!(
+            ctx.accounts.user_swap_data.owner == &safe_pump::ID && !ctx.accounts.user_swap_data.data_is_empty(),
+            HookError::SwapDataNotInitialized
+        );
+        let balance_before = ctx
+            .accounts
+            .source_token
+            .amount
+            .checked_add(amount)
+            .ok_or(HookError::MathError)?;
+
+        let signer_seeds: &[&[u8]] = &[b"hook-authority", &[ctx.bumps.hook_authority]];
+        safe_pump::cpi::record_hook_sell(
+            CpiContext::new_with_signer(
+                ctx.accounts.safe_pump_program.to_account_info(),
+                RecordHookSell {
+                    hook_authority: ctx.accounts.hook_authority.to_account_info(),
+                    contract: ctx.accounts.contract.to_account_info(),
+                    launch_config: ctx.accounts.launch_config.to_account_info(),
+                    seller: ctx.accounts.owner.to_account_info(),
+                    user_swap_data: ctx.accounts.user_swap_data.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            amount,
+            balance_before,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: ExtraAccountMetaList TLV account, written by initialize_extra_account_meta_list
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(EXTRA_ACCOUNT_COUNT)?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(has_one = mint)]
+    pub contract: Account<'info, TokenContract>,
+    pub system_program: Program<'info, System>,
+}
+
+// Order must match extra_account_metas
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: transfer authority, verified by Token-2022
+    pub owner: AccountInfo<'info>,
+    /// CHECK: ExtraAccountMetaList TLV account
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: AccountInfo<'info>,
+    pub safe_pump_program: Program<'info, SafePump>,
+    #[account(has_one = mint, has_one = launch_config)]
+    pub contract: Account<'info, TokenContract>,
+    pub launch_config: Account<'info, LaunchConfig>,
+    /// CHECK: UserSwapData PDA of the owner, checked for initialization here and validated by safe_pump::record_hook_sell
+    #[account(mut)]
+    pub user_swap_data: AccountInfo<'info>,
+    /// CHECK: PDA signing record_hook_sell
+    #[account(seeds = [b"hook-authority"], bump)]
+    pub hook_authority: AccountInfo<'info>,
+    /// CHECK: instructions sysvar
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+// Extra accounts resolved by Token-2022 for every SafePump transfer, starting at index 5
+fn extra_account_metas(contract: &Pubkey, launch_config: &Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        ExtraAccountMeta::new_with_pubkey(&safe_pump::ID, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(contract, false, false)?,
+        ExtraAccountMeta::new_with_pubkey(launch_config, false, false)?,
+        // UserSwapData is a safe_pump PDA seeded by the transfer authority and the mint
+        ExtraAccountMeta::new_external_pda_with_seeds(
+            5,
+            &[
+                Seed::Literal {
+                    bytes: b"user-swap-data".to_vec(),
+                },
+                Seed::AccountKey { index: 3 },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            true,
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[Seed::Literal {
+                bytes: b"hook-authority".to_vec(),
+            }],
+            false,
+            false,
+        )?,
+        ExtraAccountMeta::new_with_pubkey(&instructions_sysvar::ID, false, false)?,
+    ])
+}
+
+// safe_pump instructions that run check_sell_limits on their own transfers
+fn is_checked_swap(data: &[u8]) -> bool {
+    let discriminator = data.get(..8);
+    discriminator == Some(&safe_pump::instruction::GlobalTaxSwap::DISCRIMINATOR[..])
+        || discriminator == Some(&safe_pump::instruction::GlobalTaxSwapExactOut::DISCRIMINATOR[..])
+}
+
+// Rejects direct calls to the execute instruction outside of a Token-2022 transfer
+fn assert_is_transferring(source_token: &InterfaceAccount<TokenAccount>) -> Result<()> {
+    let source_info = source_token.to_account_info();
+    let data = source_info.try_borrow_data()?;
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    let extension = account.get_extension::<TransferHookAccount>()?;
+    require!(bool::from(extension.transferring), HookError::NotTransferring);
+    Ok(())
+}
+
+#[error_code]
+pub enum HookError {
+    #[msg("Transfer hook called outside of a transfer")]
+    NotTransferring,
+    #[msg("Transfers out of the wallet must be signed by the token owner")]
+    DelegatedSell,
+    #[msg("Math error")]
+    MathError,
+    #[msg("Call safe_pump::initialize_user_swap_data before transferring tokens out of the wallet")]
+    SwapDataNotInitialized,
+}
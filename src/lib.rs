@@ -1,11 +1,25 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, MintTo, Transfer, Burn},
+    associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken},
+    token_2022::{self, InitializeMint2, Token2022},
+    token_2022_extensions::{
+        metadata_pointer_initialize, non_transferable_mint_initialize, permanent_delegate_initialize,
+        MetadataPointerInitialize, NonTransferableMintInitialize, PermanentDelegateInitialize,
+    },
+    token_interface::{
+        self, Burn, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface, TokenMetadataInitialize,
+        TokenMetadataUpdateAuthority, TokenMetadataUpdateField, TransferChecked,
+    },
 };
-use solana_program::{program::invoke, clock::Clock};
+use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::instruction::AuthorityType;
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+use solana_program::{program::invoke, clock::Clock, keccak};
 use raydium_cp_swap::cpi::{accounts::CreatePool, create_pool};
-use raydium_cp_swap::instruction::SwapBaseInput;
+use raydium_cp_swap::instruction::{SwapBaseInput, SwapBaseOutput};
+use raydium_cp_swap::states::{AmmConfig, PoolState};
 
 declare_id!("AymD4HzxTN2SK6UDrCcXD2uAFk4RptvQKzMT5P9GSr32");
 
@@ -13,36 +27,105 @@ const GLOBAL_TAX: u64 = 100; // 1% in basis points
 const GLOBAL_LP_TAX: u64 = 50; // 0.5% in basis points
 const SWAPPER_REWARD_TAX: u64 = 40; // 0.4% in basis points
 const BADGE_REWARD_TAX: u64 = 10; // 0.1% in basis points
-const MAX_BADGE_HOLDERS: usize = 100; // Limit to 100 badge holders
+const BADGE_PAGE_SIZE: usize = 100; // Badge holders per registry page
+const REWARD_PRECISION: u128 = 1_000_000_000_000; // Scale of RewardDistribution.acc_reward_per_share
 const BUY_SWAPS_FOR_BADGE: u64 = 1000; // Trigger badge after 1000 buy swaps
-const ANTI_SNIPER_COOLDOWN: i64 = 120; // 120 seconds, applied only on bonding
+const BADGE_SYMBOL: &str = "SPBADGE";
+const BADGE_TIERS: usize = 4; // Bronze, silver, gold, diamond
+const BADGE_TIER_REQUIREMENTS: [BadgeTierRequirement; BADGE_TIERS] = [
+    BadgeTierRequirement { min_buy_count: BUY_SWAPS_FOR_BADGE, min_buy_volume: 10 * LAMPORTS_PER_SOL, min_holding_duration: 7 * 86_400, reward_weight: 1 },
+    BadgeTierRequirement { min_buy_count: 2_500, min_buy_volume: 50 * LAMPORTS_PER_SOL, min_holding_duration: 30 * 86_400, reward_weight: 2 },
+    BadgeTierRequirement { min_buy_count: 5_000, min_buy_volume: 250 * LAMPORTS_PER_SOL, min_holding_duration: 90 * 86_400, reward_weight: 4 },
+    BadgeTierRequirement { min_buy_count: 10_000, min_buy_volume: 1_000 * LAMPORTS_PER_SOL, min_holding_duration: 180 * 86_400, reward_weight: 8 },
+];
 const MAX_SUPPLY: u64 = 1_000_000_000_000_000_000; // 1T tokens (9 decimals)
-const MAX_BUY_PERCENT: u64 = 10; // 0.1% of supply (initial, overridden by dynamic cap)
-const MAX_SELL_PERCENT: u64 = 100; // 1% of holdings per 24 hours
-const SELL_COOLDOWN: i64 = 86_400; // 24 hours in seconds
-const REWARD_DISTRIBUTION_PERIOD: i64 = 86_400; // 24 hours
-const POOL_SOL_AMOUNT: u64 = 1_000_000_000; // 1 SOL in lamports
-const INITIAL_VAULT_AMOUNT: u64 = 100_000_000_000; // 100 SafePump tokens (9 decimals)
 const MAX_FRIENDS_WALLETS: usize = 4; // Max 4 friends
+const TOKEN_DECIMALS: u8 = 9;
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_SYMBOL_LENGTH: usize = 10;
+const MAX_URI_LENGTH: usize = 200;
 const MAX_ALLOCATION_PERCENT: u64 = 5100; // 51% max for deployer + friends
-const LIQUIDITY_THRESHOLDS: [u64; 5] = [
-    5_000_000_000,    // $5,000 in lamports (~5 SOL at $100/SOL)
-    10_000_000_000,   // $10,000
-    15_000_000_000,   // $15,000
-    20_000_000_000,   // $20,000
-    24_000_000_000,   // $24,000
-];
-const BUY_CAP_PERCENTAGES: [u64; 5] = [10, 15, 20, 22, 25]; // 0.1%, 0.15%, 0.2%, 0.22%, 0.25% in basis points
+const LIQUIDITY_TIERS: usize = 5; // Number of liquidity steps in a launch config
 const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
-const MARKET_CAP_THRESHOLD_START: u64 = 24_000_000_000; // $24,000 in lamports
-const MARKET_CAP_THRESHOLD_END: u64 = 10_000_000_000_000_000; // $10M in lamports
-const BUY_CAP_START: u64 = 25; // 0.25% at $24,000
-const BUY_CAP_END: u64 = 100; // 1% at $10M
+const RAYDIUM_FEE_RATE_DENOMINATOR: u64 = 1_000_000; // Raydium CPMM fee rates are in parts per million
+const WSOL_MINT: Pubkey = solana_program::pubkey!("So11111111111111111111111111111111111111112"); // Wrapped SOL mint
+const TRANSFER_HOOK_PROGRAM_ID: Pubkey = solana_program::pubkey!("HBmPxx1zxw7vLtaFmMHBUZPeAyCh6iZjnt59pxitwHSR"); // safe_pump_hook
+
+// Bounds enforced on every LaunchConfig
+const MAX_ANTI_SNIPER_COOLDOWN: i64 = 3_600; // 1 hour
+const MAX_SELL_COOLDOWN: i64 = 604_800; // 7 days
+const MIN_POOL_SOL_AMOUNT: u64 = LAMPORTS_PER_SOL / 10; // 0.1 SOL
+const MAX_CAP_BPS: u64 = 10_000; // 100% in basis points
+const MAX_VESTING_DURATION: i64 = 63_072_000; // 2 years
+const MIN_LP_LOCK_DURATION: i64 = 604_800; // 7 days
+const MAX_LP_LOCK_DURATION: i64 = 157_680_000; // 5 years
+const MAX_PRESALE_DURATION: i64 = 2_592_000; // 30 days
+const MAX_MERKLE_PROOF_LENGTH: usize = 32; // Enough for 2^32 allowlisted wallets
+const MAX_LAUNCH_TAX: u64 = 2_500; // 25% in basis points
+const MAX_LAUNCH_TAX_DURATION: i64 = 604_800; // 7 days
+const MAX_SNIPER_GUARD_SLOTS: u64 = 9_000; // ~1 hour at 400ms slots
+const MAX_BADGE_INACTIVITY_PERIOD: i64 = 31_536_000; // 1 year
+const SNIPER_RING_SIZE: usize = 64; // Recent buys remembered by the slot tracker, caps max_buys_per_slot
+const SELL_HISTORY_SIZE: usize = 16; // Sells remembered per user, older in-window sells are merged rather than dropped
+const USER_SWAP_DATA_SPACE: usize = 8 + 8 + 1 + (16 * SELL_HISTORY_SIZE) + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8; // Target size for migrate_user_swap_data
+
+// Bounds enforced on every registered meme coin tax schedule
+const MIN_MEME_COIN_TAX: u64 = 25; // 0.25% in basis points
+const MAX_MEME_COIN_TAX: u64 = 500; // 5% in basis points
+const _: () = assert!(GLOBAL_LP_TAX + SWAPPER_REWARD_TAX + BADGE_REWARD_TAX == GLOBAL_TAX);
 
 #[program]
 pub mod safe_pump {
     use super::*;
 
+    pub fn initialize_launch_config(
+        ctx: Context<InitializeLaunchConfig>,
+        params: LaunchConfigParams,
+    ) -> Result<()> {
+        params.validate()?;
+
+        let config = &mut ctx.accounts.launch_config;
+        config.authority = ctx.accounts.owner.key();
+        config.anti_sniper_cooldown = params.anti_sniper_cooldown;
+        config.max_sell_percent = params.max_sell_percent;
+        config.sell_cooldown = params.sell_cooldown;
+        config.pool_sol_amount = params.pool_sol_amount;
+        config.initial_vault_amount = params.initial_vault_amount;
+        config.max_allocation_percent = params.max_allocation_percent;
+        config.liquidity_thresholds = params.liquidity_thresholds;
+        config.buy_cap_percentages = params.buy_cap_percentages;
+        config.market_cap_threshold_start = params.market_cap_threshold_start;
+        config.market_cap_threshold_end = params.market_cap_threshold_end;
+        config.buy_cap_start = params.buy_cap_start;
+        config.buy_cap_end = params.buy_cap_end;
+        config.sell_unlock_buy_cap = params.sell_unlock_buy_cap;
+        config.vesting_cliff = params.vesting_cliff;
+        config.vesting_duration = params.vesting_duration;
+        config.curve_sol_target = params.curve_sol_target;
+        config.curve_virtual_sol_reserves = params.curve_virtual_sol_reserves;
+        config.curve_supply_percent = params.curve_supply_percent;
+        config.launch_tax_start = params.launch_tax_start;
+        config.launch_tax_duration = params.launch_tax_duration;
+        config.launch_tax_half_life = params.launch_tax_half_life;
+        config.launch_tax_decay = params.launch_tax_decay;
+        config.launch_tax_destination = params.launch_tax_destination;
+        config.max_wallet_percentages = params.max_wallet_percentages;
+        config.sniper_guard_slots = params.sniper_guard_slots;
+        config.max_buys_per_slot = params.max_buys_per_slot;
+        config.max_buy_volume_per_slot = params.max_buy_volume_per_slot;
+        config.badge_max_sell_percent = params.badge_max_sell_percent;
+        config.badge_inactivity_period = params.badge_inactivity_period;
+        config.badge_min_balance = params.badge_min_balance;
+        config.bump = ctx.bumps.launch_config;
+        msg!(
+            "Initialized launch config: pool_sol={}, vault_amount={}, max_allocation={} bp",
+            config.pool_sol_amount,
+            config.initial_vault_amount,
+            config.max_allocation_percent
+        );
+        Ok(())
+    }
+
     pub fn initialize_contract(
         ctx: Context<InitializeContract>,
         total_supply: u64,
@@ -52,8 +135,12 @@ pub mod safe_pump {
         friends_wallets: Vec<Pubkey>,
         friends_amounts: Vec<u64>,
         deployer_amount: u64,
+        name: String,
+        symbol: String,
+        uri: String,
     ) -> Result<()> {
         require!(total_supply <= MAX_SUPPLY, SafePumpError::InvalidSupply);
+        validate_metadata(Some(&name), Some(&symbol), Some(&uri))?;
         require!(burn_percentage <= 100, SafePumpError::InvalidBurnPercentage);
         require!(lp_percentage <= 100, SafePumpError::InvalidLpPercentage);
         require!(
@@ -64,6 +151,15 @@ pub mod safe_pump {
             friends_wallets.len() == friends_amounts.len(),
             SafePumpError::InvalidFriendsAllocation
         );
+        // Each beneficiary owns exactly one vesting schedule PDA
+        for (i, wallet) in friends_wallets.iter().enumerate() {
+            require!(
+                *wallet != Pubkey::default()
+                    && *wallet != ctx.accounts.owner.key()
+                    && !friends_wallets[..i].contains(wallet),
+                SafePumpError::InvalidFriendsAllocation
+            );
+        }
 
         // Validate allocation: Up to 51% for deployer + friends
         let total_allocation = deployer_amount
@@ -72,8 +168,9 @@ pub mod safe_pump {
         let allocation_percent = (total_allocation * 10_000)
             .checked_div(total_supply)
             .ok_or(SafePumpError::MathError)?; // In basis points
+        let config = &ctx.accounts.launch_config;
         require!(
-            allocation_percent <= MAX_ALLOCATION_PERCENT,
+            allocation_percent <= config.max_allocation_percent,
             SafePumpError::InvalidFriendsAllocation
         );
         require!(
@@ -90,11 +187,15 @@ pub mod safe_pump {
         contract.swap_count = 0;
         contract.total_swapped = 0;
         contract.bond_timestamp = 0;
-        contract.vault_sol_balance = POOL_SOL_AMOUNT;
+        contract.bond_slot = 0;
+        contract.pool_vault = Pubkey::default();
+        contract.pool_sol_vault = Pubkey::default();
+        contract.pool_state = Pubkey::default();
+        contract.vault_sol_balance = 0;
         contract.vault_token_balance = (total_supply * lp_percentage as u64) / 100;
         contract.burned_tokens = 0;
         contract.burn_percentage = burn_percentage;
-        contract.buy_cap_percentage = BUY_CAP_PERCENTAGES[0]; // Start at 0.1%
+        contract.buy_cap_percentage = config.buy_cap_percentages[0]; // Start at first tier
         contract.sell_lock_active = true; // Lock sells initially
         contract.liquidity_threshold_index = 0; // Start at first step
         contract.friends_wallets = {
@@ -112,77 +213,184 @@ pub mod safe_pump {
             arr
         };
         contract.deployer_amount = deployer_amount;
+        contract.launch_config = config.key();
+        contract.mint = ctx.accounts.mint.key();
+        contract.launch_stage = LaunchStage::Created;
+        contract.metadata_frozen = false;
+        contract.presale_active = false;
+        contract.mint_renounced = false;
+        contract.freeze_renounced = false;
+        contract.token_tax_balance = 0;
+        contract.token_tax_converted = 0;
         contract.bump = ctx.bumps.contract;
 
-        // Mint allocation to deployer and friends
-        let allocation_after_burn = if burn_percentage > 0 {
-            let burn_amount = total_allocation * burn_percentage as u64 / 100;
-            total_allocation - burn_amount
-        } else {
-            total_allocation
+        // Fund the metadata TLV entry before Token-2022 reallocates the mint
+        let token_metadata = TokenMetadata {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
         };
-        let deployer_allocation = (deployer_amount * (100 - burn_percentage as u64)) / 100;
+        let metadata_space = token_metadata.tlv_size_of()?;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let required_lamports = Rent::get()?.minimum_balance(mint_info.data_len() + metadata_space);
+        let extra_lamports = required_lamports.saturating_sub(mint_info.lamports());
+        if extra_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: mint_info.clone(),
+                    },
+                ),
+                extra_lamports,
+            )?;
+        }
 
-        // Mint to deployer
-        token::mint_to(
+        token_interface::token_metadata_initialize(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.deployer_ata.to_account_info(),
-                    authority: ctx.accounts.contract.to_account_info(),
+                TokenMetadataInitialize {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: mint_info.clone(),
+                    metadata: mint_info,
+                    mint_authority: contract.to_account_info(),
+                    update_authority: contract.to_account_info(),
                 },
                 &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
             ),
-            deployer_allocation,
-        )?;
-        msg!("Minted {} SafePump tokens to deployer", deployer_allocation);
-
-        // Mint to friends
-        for (i, (wallet, &amount)) in friends_wallets.iter().zip(friends_amounts.iter()).enumerate() {
-            let friend_allocation = (amount * (100 - burn_percentage as u64)) / 100;
-            let friend_ata = get_associated_token_address(wallet, &ctx.accounts.mint.key());
-            invoke(
-                &spl_associated_token_account::instruction::create_associated_token_account(
-                    &ctx.accounts.owner.key(),
-                    wallet,
-                    &ctx.accounts.mint.key(),
-                    &spl_token::ID,
-                ),
-                &[
-                    ctx.accounts.owner.to_account_info(),
-                    friend_ata.to_account_info(),
-                    ctx.accounts.token_program.to_account_info(),
+            name.clone(),
+            symbol.clone(),
+            uri,
+        )?;
+        msg!(
+            "Initialized SafePump contract: supply={}, treasury={}, lp_percentage={}, name={}, symbol={}",
+            total_supply,
+            treasury_wallet,
+            lp_percentage,
+            name,
+            symbol
+        );
+        Ok(())
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: Option<String>,
+        symbol: Option<String>,
+        uri: Option<String>,
+    ) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        require!(!contract.metadata_frozen, SafePumpError::MetadataFrozen);
+        validate_metadata(name.as_ref(), symbol.as_ref(), uri.as_ref())?;
+
+        let signer_seeds: &[&[u8]] = &[b"contract", ctx.accounts.owner.key.as_ref(), &[contract.bump]];
+        let updates = [(Field::Name, name), (Field::Symbol, symbol), (Field::Uri, uri)];
+        for (field, value) in updates {
+            if let Some(value) = value {
+                msg!("Updating metadata field {:?} to {}", field, value);
+                token_interface::token_metadata_update_field(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TokenMetadataUpdateField {
+                            program_id: ctx.accounts.token_program.to_account_info(),
+                            metadata: ctx.accounts.mint.to_account_info(),
+                            update_authority: contract.to_account_info(),
+                        },
+                        &[signer_seeds],
+                    ),
+                    field,
+                    value,
+                )?;
+            }
+        }
+
+        // Longer fields grow the mint, keep it rent exempt
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let required_lamports = Rent::get()?.minimum_balance(mint_info.data_len());
+        let extra_lamports = required_lamports.saturating_sub(mint_info.lamports());
+        if extra_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
                     ctx.accounts.system_program.to_account_info(),
-                    ctx.accounts.mint.to_account_info(),
-                ],
-            )?;
-            token::mint_to(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    MintTo {
-                        mint: ctx.accounts.mint.to_account_info(),
-                        to: friend_ata.to_account_info(),
-                        authority: ctx.accounts.contract.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.owner.to_account_info(),
+                        to: mint_info,
                     },
-                    &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
                 ),
-                friend_allocation,
+                extra_lamports,
             )?;
-            msg!("Minted {} tokens to friend[{}]: {}", friend_allocation, i, wallet);
         }
+        Ok(())
+    }
+
+    pub fn freeze_metadata(ctx: Context<UpdateMetadata>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        require!(!contract.metadata_frozen, SafePumpError::MetadataFrozen);
+
+        // Clearing the update authority makes the freeze verifiable on the mint itself
+        token_interface::token_metadata_update_authority(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataUpdateAuthority {
+                    program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: ctx.accounts.mint.to_account_info(),
+                    current_authority: contract.to_account_info(),
+                    new_authority: contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+            ),
+            OptionalNonZeroPubkey::default(),
+        )?;
+
+        contract.metadata_frozen = true;
+        msg!("Metadata permanently frozen for mint: {}", contract.mint);
+        Ok(())
+    }
+
+    pub fn mint_allocations(ctx: Context<MintAllocations>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        contract.require_stage(LaunchStage::Created)?;
+        let signer_seeds: &[&[u8]] = &[b"contract", ctx.accounts.owner.key.as_ref(), &[contract.bump]];
+
+        // Deployer and friends allocations are minted into the vesting escrow and
+        // released through per-beneficiary VestingSchedule accounts
+        let burn_percentage = contract.burn_percentage;
+        let mut vested_total = vesting_allocation(contract.deployer_amount, burn_percentage)?;
+        for &amount in contract.friends_amounts.iter() {
+            vested_total = vested_total
+                .checked_add(vesting_allocation(amount, burn_percentage)?)
+                .ok_or(SafePumpError::MathError)?;
+        }
+        contract.vesting_escrow = ctx.accounts.vesting_escrow.key();
+        contract.vested_tokens = vested_total;
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vesting_escrow.to_account_info(),
+                    authority: contract.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            vested_total,
+        )?;
+        msg!("Minted {} SafePump tokens to vesting escrow", vested_total);
 
-        // Mint lp_percentage% to token0_vault
-        let pool_token_amount = (total_supply * lp_percentage as u64) / 100;
-        token::mint_to(
+        // Mint the LP share to token0_vault
+        let pool_token_amount = contract.vault_token_balance;
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
                     mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.vault.to_account_info(),
-                    authority: ctx.accounts.contract.to_account_info(),
+                    authority: contract.to_account_info(),
                 },
-                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+                &[signer_seeds],
             ),
             pool_token_amount,
         )?;
@@ -191,14 +399,15 @@ pub mod safe_pump {
         // Burn tokens from vault if specified
         if burn_percentage > 0 {
             let burn_amount = pool_token_amount * burn_percentage as u64 / 100;
-            token::burn(
-                CpiContext::new(
+            token_interface::burn(
+                CpiContext::new_with_signer(
                     ctx.accounts.token_program.to_account_info(),
                     Burn {
                         mint: ctx.accounts.mint.to_account_info(),
                         from: ctx.accounts.vault.to_account_info(),
-                        authority: ctx.accounts.owner.to_account_info(),
+                        authority: contract.to_account_info(),
                     },
+                    &[signer_seeds],
                 ),
                 burn_amount,
             )?;
@@ -207,47 +416,81 @@ pub mod safe_pump {
             msg!("Burned {}% of vault tokens: {}", burn_percentage, burn_amount);
         }
 
-        // Initialize badge vault with 100 SafePump tokens
-        token::mint_to(
+        contract.launch_stage = LaunchStage::Allocated;
+        Ok(())
+    }
+
+    pub fn fund_vaults(ctx: Context<FundVaults>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        let config = &ctx.accounts.launch_config;
+        contract.require_stage(LaunchStage::Allocated)?;
+        let signer_seeds: &[&[u8]] = &[b"contract", ctx.accounts.owner.key.as_ref(), &[contract.bump]];
+
+        // Initialize badge vault with the configured SafePump tokens
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
                     mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.badge_vault.to_account_info(),
-                    authority: ctx.accounts.contract.to_account_info(),
+                    authority: contract.to_account_info(),
                 },
-                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+                &[signer_seeds],
             ),
-            INITIAL_VAULT_AMOUNT,
+            config.initial_vault_amount,
         )?;
 
-        // Initialize swap rewards vault with 100 SafePump tokens
-        token::mint_to(
+        // Initialize swap rewards vault with the configured SafePump tokens
+        token_interface::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
                     mint: ctx.accounts.mint.to_account_info(),
                     to: ctx.accounts.swap_rewards_vault.to_account_info(),
-                    authority: ctx.accounts.contract.to_account_info(),
+                    authority: contract.to_account_info(),
                 },
-                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+                &[signer_seeds],
             ),
-            INITIAL_VAULT_AMOUNT,
+            config.initial_vault_amount,
         )?;
 
-        // Fund WSOL ATA with 1 SOL
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.owner_wsol_ata.to_account_info(),
-                    to: ctx.accounts.sol_vault.to_account_info(),
-                    authority: ctx.accounts.owner.to_account_info(),
-                },
-            ),
-            POOL_SOL_AMOUNT,
-        )?;
-        msg!("Transferred {} lamports to vault SOL account", POOL_SOL_AMOUNT);
+        // Curve launches raise their pool SOL on the bonding curve instead
+        if config.curve_sol_target == 0 {
+            let owner_wsol_ata = ctx
+                .accounts
+                .owner_wsol_ata
+                .as_ref()
+                .ok_or(SafePumpError::MissingLaunchAccount)?;
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    ctx.accounts.wsol_token_program.to_account_info(),
+                    TransferChecked {
+                        from: owner_wsol_ata.to_account_info(),
+                        mint: ctx.accounts.wsol_mint.to_account_info(),
+                        to: ctx.accounts.sol_vault.to_account_info(),
+                        authority: ctx.accounts.owner.to_account_info(),
+                    },
+                ),
+                config.pool_sol_amount,
+                ctx.accounts.wsol_mint.decimals,
+            )?;
+            contract.vault_sol_balance = config.pool_sol_amount;
+            msg!("Transferred {} lamports to vault SOL account", config.pool_sol_amount);
+        }
+
+        contract.token_tax_vault = ctx.accounts.token_tax_vault.key();
+        contract.launch_stage = LaunchStage::VaultsFunded;
+        Ok(())
+    }
+
+    pub fn bond_to_raydium(ctx: Context<BondToRaydium>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        let config = &ctx.accounts.launch_config;
+        contract.require_stage(LaunchStage::VaultsFunded)?;
+        // Curve launches bond through migrate_to_raydium once the raise completes
+        require!(config.curve_sol_target == 0, SafePumpError::CurveNotComplete);
+        // Presale SOL must be settled before it seeds the pool
+        require!(!contract.presale_active, SafePumpError::PresaleActive);
 
         // Bond to Raydium CPMM
         let cpi_accounts = CreatePool {
@@ -273,9 +516,9 @@ pub mod safe_pump {
         )?;
 
         // Burn LP tokens if specified
-        if burn_percentage > 0 {
-            let burn_amount = ctx.accounts.lp_vault.amount * burn_percentage as u64 / 100;
-            token::burn(
+        if contract.burn_percentage > 0 {
+            let burn_amount = ctx.accounts.lp_vault.amount * contract.burn_percentage as u64 / 100;
+            token_interface::burn(
                 CpiContext::new(
                     ctx.accounts.token_program.to_account_info(),
                     Burn {
@@ -286,683 +529,4042 @@ pub mod safe_pump {
                 ),
                 burn_amount,
             )?;
-            msg!("Burned {}% of LP tokens: {}", burn_percentage, burn_amount);
+            msg!("Burned {}% of LP tokens: {}", contract.burn_percentage, burn_amount);
         }
 
-        contract.bond_timestamp = Clock::get()?.unix_timestamp;
-        msg!(
-            "Initialized SafePump contract: supply={}, treasury={}, lp_percentage={}, bonded to Raydium",
-            total_supply,
-            treasury_wallet,
-            lp_percentage
-        );
+        let clock = Clock::get()?;
+        contract.bond_timestamp = clock.unix_timestamp;
+        contract.bond_slot = clock.slot;
+        contract.pool_vault = ctx.accounts.vault.key();
+        contract.pool_sol_vault = ctx.accounts.sol_vault.key();
+        contract.pool_state = ctx.accounts.pool_state.key();
+        contract.launch_stage = LaunchStage::Bonded;
+        msg!("Bonded SafePump contract to Raydium");
         Ok(())
     }
 
-    pub fn initialize_badge_holders(ctx: Context<InitializeBadgeHolders>) -> Result<()> {
-        let badge_holders = &mut ctx.accounts.badge_holders;
-        badge_holders.holders = [Pubkey::default(); MAX_BADGE_HOLDERS];
-        badge_holders.buy_swap_count = [(Pubkey::default(), 0); MAX_BADGE_HOLDERS];
-        badge_holders.holder_count = 0;
-        badge_holders.bump = ctx.bumps.badge_holders;
-        msg!("Initialized badge holders for mint: {}", ctx.accounts.mint.key());
+    pub fn finalize_launch(ctx: Context<FinalizeLaunch>) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        contract.require_stage(LaunchStage::Bonded)?;
+        require!(!contract.presale_active, SafePumpError::PresaleActive);
+        let signer_seeds: &[&[u8]] = &[b"contract", ctx.accounts.owner.key.as_ref(), &[contract.bump]];
+
+        // Renounce mint authority so supply is fixed forever
+        if ctx.accounts.mint.mint_authority.is_some() {
+            token_interface::set_authority(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    SetAuthority {
+                        current_authority: contract.to_account_info(),
+                        account_or_mint: ctx.accounts.mint.to_account_info(),
+                    },
+                    &[signer_seeds],
+                ),
+                AuthorityType::MintTokens,
+                None,
+            )?;
+        }
+        contract.mint_renounced = true;
+
+        // Renounce freeze authority so holder accounts can never be frozen
+        if ctx.accounts.mint.freeze_authority.is_some() {
+            token_interface::set_authority(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    SetAuthority {
+                        current_authority: contract.to_account_info(),
+                        account_or_mint: ctx.accounts.mint.to_account_info(),
+                    },
+                    &[signer_seeds],
+                ),
+                AuthorityType::FreezeAccount,
+                None,
+            )?;
+        }
+        contract.freeze_renounced = true;
+
+        contract.launch_stage = LaunchStage::Finalized;
+        msg!(
+            "Finalized SafePump launch for mint: {}, mint and freeze authority renounced",
+            contract.mint
+        );
         Ok(())
     }
 
-    pub fn register_meme_coin(ctx: Context<RegisterMemeCoin>, program_id: Pubkey) -> Result<()> {
-        let registry = &mut ctx.accounts.meme_coin_registry;
-        let deployer = ctx.accounts.deployer.key();
+    pub fn create_vesting_schedule(ctx: Context<CreateVestingSchedule>, beneficiary: Pubkey) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        let config = &ctx.accounts.launch_config;
+        require!(contract.is_initialized, SafePumpError::NotInitialized);
+        // Vesting starts at bond time, so curve launches must migrate first
+        require!(contract.launch_stage >= LaunchStage::Bonded, SafePumpError::NotBonded);
 
-        require!(
-            !registry.meme_coins.iter().any(|(pid, _)| *pid == program_id),
-            SafePumpError::MemeCoinAlreadyRegistered
-        );
+        let amount = if beneficiary == ctx.accounts.owner.key() {
+            contract.deployer_amount
+        } else {
+            let index = contract
+                .friends_wallets
+                .iter()
+                .position(|wallet| *wallet == beneficiary && *wallet != Pubkey::default())
+                .ok_or(SafePumpError::NotVestingBeneficiary)?;
+            contract.friends_amounts[index]
+        };
+        let total_amount = vesting_allocation(amount, contract.burn_percentage)?;
+        require!(total_amount > 0, SafePumpError::NotVestingBeneficiary);
 
-        registry.meme_coins[registry.meme_coin_count as usize] = (program_id, deployer);
-        registry.meme_coin_count += 1;
-        registry.bump = ctx.bumps.meme_coin_registry;
-        msg!("Registered meme coin: program_id={}, deployer={}", program_id, deployer);
+        let start_timestamp = contract.bond_timestamp;
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        schedule.contract = contract.key();
+        schedule.beneficiary = beneficiary;
+        schedule.total_amount = total_amount;
+        schedule.claimed_amount = 0;
+        schedule.start_timestamp = start_timestamp;
+        schedule.cliff_timestamp = start_timestamp
+            .checked_add(config.vesting_cliff)
+            .ok_or(SafePumpError::MathError)?;
+        schedule.end_timestamp = start_timestamp
+            .checked_add(config.vesting_duration)
+            .ok_or(SafePumpError::MathError)?;
+        schedule.bump = ctx.bumps.vesting_schedule;
+        msg!(
+            "Created vesting schedule: beneficiary={}, amount={}, cliff={}, end={}",
+            beneficiary,
+            total_amount,
+            schedule.cliff_timestamp,
+            schedule.end_timestamp
+        );
         Ok(())
     }
 
-    pub fn global_tax_swap(ctx: Context<GlobalTaxSwap>, amount: u64, is_buy: bool, meme_program_id: Pubkey) -> Result<()> {
-        let contract = &mut ctx.accounts.contract;
-        let badge_holders = &mut ctx.accounts.badge_holders;
-        let user_swap_data = &mut ctx.accounts.user_swap_data;
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let schedule = &mut ctx.accounts.vesting_schedule;
         let clock = Clock::get()?;
 
-        require!(contract.is_initialized, SafePumpError::NotInitialized);
+        let claimable = schedule
+            .vested_amount(clock.unix_timestamp)?
+            .checked_sub(schedule.claimed_amount)
+            .ok_or(SafePumpError::MathError)?;
+        require!(claimable > 0, SafePumpError::NothingToClaim);
 
-        if contract.swap_count == 0 {
-            require!(
-                clock.unix_timestamp - contract.bond_timestamp >= ANTI_SNIPER_COOLDOWN,
-                SafePumpError::AntiSniperCooldown
-            );
-        }
+        transfer_safepump(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vesting_escrow.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.beneficiary_ata.to_account_info(),
+                    authority: ctx.accounts.contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[ctx.accounts.contract.bump]]],
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            claimable,
+            ctx.accounts.mint.decimals,
+        )?;
 
-        let registry = &ctx.accounts.meme_coin_registry;
-        let is_safepump_swap = meme_program_id == ctx.accounts.safepump_mint.key();
-        require!(
-            is_safepump_swap || registry.meme_coins.iter().any(|(pid, _)| *pid == meme_program_id),
-            SafePumpError::MemeCoinNotRegistered
+        schedule.claimed_amount = schedule.claimed_amount.checked_add(claimable).ok_or(SafePumpError::MathError)?;
+        let contract = &mut ctx.accounts.contract;
+        contract.vested_tokens = contract.vested_tokens.checked_sub(claimable).ok_or(SafePumpError::MathError)?;
+        msg!(
+            "Claimed {} vested tokens for {} ({} of {} claimed)",
+            claimable,
+            schedule.beneficiary,
+            schedule.claimed_amount,
+            schedule.total_amount
         );
+        Ok(())
+    }
 
-        // Update buy cap based on liquidity and market cap
-        let pool_sol_amount = ctx.accounts.sol_vault.amount; // SOL balance in lamports
-        let pool_token_amount = ctx.accounts.vault.amount; // Token balance in lamports
-        let current_liquidity = pool_sol_amount; // SOL balance in lamports
+    pub fn initialize_bonding_curve(ctx: Context<InitializeBondingCurve>) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        let config = &ctx.accounts.launch_config;
+        contract.require_stage(LaunchStage::VaultsFunded)?;
+        require!(config.curve_sol_target > 0, SafePumpError::CurveDisabled);
 
-        // Update buy cap based on liquidity thresholds
-        let current_index = contract.liquidity_threshold_index as usize;
-        if current_index < LIQUIDITY_THRESHOLDS.len() - 1 && current_liquidity >= LIQUIDITY_THRESHOLDS[current_index + 1] {
-            contract.liquidity_threshold_index += 1;
-            contract.buy_cap_percentage = BUY_CAP_PERCENTAGES[contract.liquidity_threshold_index as usize];
-            msg!("Updated buy cap to {} bp at liquidity {} lamports", contract.buy_cap_percentage, current_liquidity);
-        }
+        let curve_tokens = (contract.vault_token_balance as u128)
+            .checked_mul(config.curve_supply_percent as u128)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(10_000)
+            .ok_or(SafePumpError::MathError)? as u64;
 
-        // Estimate market cap: (pool_sol_amount / pool_token_amount) * total_supply
-        let market_cap = if pool_token_amount > 0 {
-            (pool_sol_amount as u128)
-                .checked_mul(contract.total_supply as u128)
-                .ok_or(SafePumpError::MathError)?
-                .checked_div(pool_token_amount as u128)
-                .ok_or(SafePumpError::MathError)?
-                as u64
-        } else {
-            0
-        };
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.contract = contract.key();
+        curve.vault = ctx.accounts.vault.key();
+        curve.sol_vault = ctx.accounts.sol_vault.key();
+        curve.virtual_sol_reserves = config.curve_virtual_sol_reserves;
+        curve.virtual_token_reserves = contract.vault_token_balance;
+        curve.real_sol_reserves = 0;
+        curve.real_token_reserves = curve_tokens;
+        curve.sol_target = config.curve_sol_target;
+        curve.complete = false;
+        curve.migrated = false;
+        curve.bump = ctx.bumps.bonding_curve;
+        msg!(
+            "Initialized bonding curve: virtual_sol={}, virtual_tokens={}, curve_tokens={}, target={}",
+            curve.virtual_sol_reserves,
+            curve.virtual_token_reserves,
+            curve.real_token_reserves,
+            curve.sol_target
+        );
+        Ok(())
+    }
 
-        // Scale buy cap from 0.25% to 1% between $24,000 and $10M market cap
-        if market_cap > MARKET_CAP_THRESHOLD_START {
-            let market_cap_range = MARKET_CAP_THRESHOLD_END - MARKET_CAP_THRESHOLD_START;
-            let buy_cap_range = BUY_CAP_END - BUY_CAP_START;
-            let market_cap_progress = market_cap.saturating_sub(MARKET_CAP_THRESHOLD_START);
-            let buy_cap_increase = (market_cap_progress as u128)
-                .checked_mul(buy_cap_range as u128)
-                .ok_or(SafePumpError::MathError)?
-                .checked_div(market_cap_range as u128)
-                .ok_or(SafePumpError::MathError)?
-                as u64;
-            contract.buy_cap_percentage = BUY_CAP_START + buy_cap_increase;
-            if contract.buy_cap_percentage > BUY_CAP_END {
-                contract.buy_cap_percentage = BUY_CAP_END; // Cap at 1%
-            }
-            msg!("Updated buy cap to {} bp at market cap {} lamports", contract.buy_cap_percentage, market_cap);
-        }
+    pub fn buy_on_curve(ctx: Context<TradeOnCurve>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
+        let curve = &mut ctx.accounts.bonding_curve;
+        require!(!curve.complete, SafePumpError::CurveComplete);
+        require!(sol_amount > 0, SafePumpError::InvalidAmount);
 
-        // Unlock sells when buy cap reaches or exceeds 0.25% (25 bp)
-        if contract.buy_cap_percentage >= 25 && contract.sell_lock_active {
-            contract.sell_lock_active = false;
-            msg!("Sell lock lifted: buy_cap={} bp, market_cap={} lamports", contract.buy_cap_percentage, market_cap);
-        }
+        let tokens_out = curve.tokens_out_for_sol(sol_amount)?;
+        require!(tokens_out > 0, SafePumpError::InvalidAmount);
+        require!(tokens_out <= curve.real_token_reserves, SafePumpError::InsufficientCurveReserves);
+        require!(tokens_out >= min_tokens_out, SafePumpError::SlippageExceeded);
 
-        // Enforce buy cap
+        let contract = &mut ctx.accounts.contract;
         let max_buy_amount = contract.total_supply * contract.buy_cap_percentage / 10_000;
-        require!(amount <= max_buy_amount, SafePumpError::ExceedsMaxBuy);
+        require!(tokens_out <= max_buy_amount, SafePumpError::ExceedsMaxBuy);
+        let balance_after = ctx
+            .accounts
+            .user_token_ata
+            .amount
+            .checked_add(tokens_out)
+            .ok_or(SafePumpError::MathError)?;
+        check_max_wallet(
+            &contract.key(),
+            contract,
+            &ctx.accounts.launch_config,
+            &ctx.accounts.user_token_ata.key(),
+            &ctx.accounts.user.key(),
+            balance_after,
+        )?;
 
-        // Enforce sell lock if buy cap is below 0.25% (25 bp)
-        if !is_buy {
-            require!(!contract.sell_lock_active, SafePumpError::SellLockActive);
-            let user_balance = ctx.accounts.user_ata.amount;
-            require!(amount <= user_balance * MAX_SELL_PERCENT / 10_000, SafePumpError::ExceedsMaxSell);
-            require!(
-                clock.unix_timestamp - user_swap_data.last_sell_timestamp >= SELL_COOLDOWN,
-                SafePumpError::SellCooldownNotMet
-            );
-            user_swap_data.last_sell_timestamp = clock.unix_timestamp;
-        }
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.wsol_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_wsol_ata.to_account_info(),
+                    mint: ctx.accounts.wsol_mint.to_account_info(),
+                    to: ctx.accounts.sol_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            sol_amount,
+            ctx.accounts.wsol_mint.decimals,
+        )?;
+        transfer_safepump(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_ata.to_account_info(),
+                    authority: contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            tokens_out,
+            ctx.accounts.mint.decimals,
+        )?;
 
-        let total_tax = amount.checked_mul(GLOBAL_TAX).ok_or(SafePumpError::MathError)? / 10_000;
-        let lp_tax = total_tax.checked_mul(GLOBAL_LP_TAX).ok_or(SafePumpError::MathError)? / GLOBAL_TAX;
-        let swapper_tax = total_tax.checked_mul(SWAPPER_REWARD_TAX).ok_or(SafePumpError::MathError)? / GLOBAL_TAX;
-        let badge_tax = total_tax.checked_mul(BADGE_REWARD_TAX).ok_or(SafePumpError::MathError)? / GLOBAL_TAX;
-        let lp_amount = amount.checked_sub(total_tax).ok_or(SafePumpError::MathError)?;
+        curve.virtual_sol_reserves = curve.virtual_sol_reserves.checked_add(sol_amount).ok_or(SafePumpError::MathError)?;
+        curve.virtual_token_reserves = curve.virtual_token_reserves.checked_sub(tokens_out).ok_or(SafePumpError::MathError)?;
+        curve.real_sol_reserves = curve.real_sol_reserves.checked_add(sol_amount).ok_or(SafePumpError::MathError)?;
+        curve.real_token_reserves = curve.real_token_reserves.checked_sub(tokens_out).ok_or(SafePumpError::MathError)?;
+        contract.vault_sol_balance = contract.vault_sol_balance.checked_add(sol_amount).ok_or(SafePumpError::MathError)?;
+        contract.vault_token_balance = contract.vault_token_balance.checked_sub(tokens_out).ok_or(SafePumpError::MathError)?;
+        msg!("Bought {} tokens on curve for {} lamports", tokens_out, sol_amount);
 
-        if is_buy {
-            let user_key = ctx.accounts.user.key();
-            let index = badge_holders.buy_swap_count.iter().position(|(pubkey, _)| *pubkey == user_key);
-            if let Some(idx) = index {
-                badge_holders.buy_swap_count[idx].1 += 1;
-            } else if badge_holders.holder_count < MAX_BADGE_HOLDERS as u64 {
-                badge_holders.buy_swap_count[badge_holders.holder_count as usize] = (user_key, 1);
-                badge_holders.holder_count += 1;
-            }
-            msg!("Updated buy swap count for user {}: {}", user_key, badge_holders.buy_swap_count[index.unwrap_or(badge_holders.holder_count as usize - 1)].1);
+        if curve.real_sol_reserves >= curve.sol_target || curve.real_token_reserves == 0 {
+            curve.complete = true;
+            msg!("Bonding curve complete: raised {} lamports", curve.real_sol_reserves);
         }
+        Ok(())
+    }
 
-        let reward_dist = &mut ctx.accounts.reward_distribution;
-        if reward_dist.swap_count < MAX_BADGE_HOLDERS as u64 {
-            reward_dist.swapper_rewards[reward_dist.swap_count as usize] = (ctx.accounts.user.key(), swapper_tax);
-            reward_dist.swap_count += 1;
-        }
-        reward_dist.badge_rewards += badge_tax;
-        if reward_dist.last_distribution_timestamp == 0 {
-            reward_dist.last_distribution_timestamp = clock.unix_timestamp;
-        }
+    pub fn sell_on_curve(ctx: Context<TradeOnCurve>, token_amount: u64, min_sol_out: u64) -> Result<()> {
+        let curve = &mut ctx.accounts.bonding_curve;
+        require!(!curve.complete, SafePumpError::CurveComplete);
+        require!(token_amount > 0, SafePumpError::InvalidAmount);
 
-        if is_safepump_swap {
-            if is_buy {
-                let cpi_accounts = raydium_cp_swap::cpi::accounts::SwapBaseIn {
-                    pool_state: ctx.accounts.pool_state.to_account_info(),
-                    user_source_token: ctx.accounts.user_ata.to_account_info(),
-                    user_destination_token: ctx.accounts.user_safepump_ata.to_account_info(),
-                    token_0_vault: ctx.accounts.vault.to_account_info(),
-                    token_1_vault: ctx.accounts.sol_vault.to_account_info(),
-                    token_program: ctx.accounts.token_program.to_account_info(),
-                    remaining_accounts: ctx.remaining_accounts.to_vec(),
-                };
-                let cpi_program = ctx.accounts.raydium_program.to_account_info();
-                let instruction = SwapBaseInput {
-                    amount: lp_amount,
-                    minimum_amount_out: 0,
-                };
-                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-                raydium_cp_swap::cpi::swap_base_in(cpi_ctx, instruction)?;
-                msg!("Performed SafePump swap: amount={} lamports to LP", lp_amount);
+        let sol_out = curve.sol_out_for_tokens(token_amount)?;
+        require!(sol_out > 0, SafePumpError::InvalidAmount);
+        require!(sol_out <= curve.real_sol_reserves, SafePumpError::InsufficientCurveReserves);
+        require!(sol_out >= min_sol_out, SafePumpError::SlippageExceeded);
 
-                token::transfer(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.user_ata.to_account_info(),
-                            to: ctx.accounts.sol_vault.to_account_info(),
-                            authority: ctx.accounts.user.to_account_info(),
-                        },
-                    ),
-                    total_tax,
-                )?;
-                contract.vault_sol_balance += total_tax;
-                msg!("Transferred {} lamports (tax) to SafePump sol_vault", total_tax);
-            } else {
-                let cpi_accounts = raydium_cp_swap::cpi::accounts::SwapBaseIn {
-                    pool_state: ctx.accounts.pool_state.to_account_info(),
-                    user_source_token: ctx.accounts.user_safepump_ata.to_account_info(),
-                    user_destination_token: ctx.accounts.user_ata.to_account_info(),
-                    token_0_vault: ctx.accounts.vault.to_account_info(),
-                    token_1_vault: ctx.accounts.sol_vault.to_account_info(),
-                    token_program: ctx.accounts.token_program.to_account_info(),
-                    remaining_accounts: ctx.remaining_accounts.to_vec(),
-                };
-                let cpi_program = ctx.accounts.raydium_program.to_account_info();
-                let instruction = SwapBaseInput {
-                    amount,
-                    minimum_amount_out: 0,
-                };
-                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-                raydium_cp_swap::cpi::swap_base_in(cpi_ctx, instruction)?;
-                msg!("Performed SafePump sell: amount={} tokens", amount);
+        let contract = &mut ctx.accounts.contract;
+        transfer_safepump(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_ata.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            token_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.wsol_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.sol_vault.to_account_info(),
+                    mint: ctx.accounts.wsol_mint.to_account_info(),
+                    to: ctx.accounts.user_wsol_ata.to_account_info(),
+                    authority: contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+            ),
+            sol_out,
+            ctx.accounts.wsol_mint.decimals,
+        )?;
 
-                token::transfer(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.user_safepump_ata.to_account_info(),
-                            to: ctx.accounts.sol_vault.to_account_info(),
-                            authority: ctx.accounts.user.to_account_info(),
-                        },
-                    ),
-                    total_tax,
-                )?;
-                contract.vault_sol_balance += total_tax;
-                msg!("Transferred {} lamports (tax) to SafePump sol_vault", total_tax);
-            }
+        curve.virtual_sol_reserves = curve.virtual_sol_reserves.checked_sub(sol_out).ok_or(SafePumpError::MathError)?;
+        curve.virtual_token_reserves = curve.virtual_token_reserves.checked_add(token_amount).ok_or(SafePumpError::MathError)?;
+        curve.real_sol_reserves = curve.real_sol_reserves.checked_sub(sol_out).ok_or(SafePumpError::MathError)?;
+        curve.real_token_reserves = curve.real_token_reserves.checked_add(token_amount).ok_or(SafePumpError::MathError)?;
+        contract.vault_sol_balance = contract.vault_sol_balance.checked_sub(sol_out).ok_or(SafePumpError::MathError)?;
+        contract.vault_token_balance = contract.vault_token_balance.checked_add(token_amount).ok_or(SafePumpError::MathError)?;
+        msg!("Sold {} tokens on curve for {} lamports", token_amount, sol_out);
+        Ok(())
+    }
 
-            if lp_tax > 0 {
-                token::transfer(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.user_ata.to_account_info(),
-                            to: ctx.accounts.lp_vault.to_account_info(),
-                            authority: ctx.accounts.user.to_account_info(),
-                        },
-                    ),
-                    lp_tax,
-                )?;
-                msg!("Transferred {} lamports to SafePump LP vault", lp_tax);
-            }
-        } else {
-            let meme_coin_data = ctx.accounts.meme_coin_data.load()?;
-            if meme_coin_data.bond_timestamp > 0 {
-                let cpi_accounts = raydium_cp_swap::cpi::accounts::SwapBaseIn {
-                    pool_state: ctx.accounts.pool_state.to_account_info(),
-                    user_source_token: ctx.accounts.user_ata.to_account_info(),
-                    user_destination_token: ctx.accounts.user_safepump_ata.to_account_info(),
-                    token_0_vault: ctx.accounts.vault.to_account_info(),
-                    token_1_vault: ctx.accounts.sol_vault.to_account_info(),
-                    token_program: ctx.accounts.token_program.to_account_info(),
-                    remaining_accounts: ctx.remaining_accounts.to_vec(),
-                };
-                let cpi_program = ctx.accounts.raydium_program.to_account_info();
-                let instruction = SwapBaseInput {
-                    amount: lp_amount,
-                    minimum_amount_out: 0,
-                };
-                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-                raydium_cp_swap::cpi::swap_base_in(cpi_ctx, instruction)?;
-                msg!("Performed meme coin swap: amount={} lamports to meme coin LP", lp_amount);
-            } else {
-                token::transfer(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.user_ata.to_account_info(),
-                            to: ctx.accounts.vault.to_account_info(),
-                            authority: ctx.accounts.user.to_account_info(),
-                        },
-                    ),
-                    lp_amount,
-                )?;
-                msg!("Transferred {} lamports to pre-bonded meme coin vault", lp_amount);
-            }
+    pub fn migrate_to_raydium(ctx: Context<MigrateToRaydium>) -> Result<()> {
+        let curve = &mut ctx.accounts.bonding_curve;
+        require!(curve.complete, SafePumpError::CurveNotComplete);
+        require!(!curve.migrated, SafePumpError::AlreadyBonded);
 
-            token::transfer(
-                CpiContext::new(
+        let contract = &mut ctx.accounts.contract;
+        contract.require_stage(LaunchStage::VaultsFunded)?;
+        // Presale SOL must be settled before it seeds the pool
+        require!(!contract.presale_active, SafePumpError::PresaleActive);
+
+        // The pool opens at the curve's closing price; vault tokens beyond that would dump it on curve buyers
+        let pool_tokens = curve.pool_tokens_for_sol(contract.vault_sol_balance)?.min(contract.vault_token_balance);
+        let excess_tokens = contract.vault_token_balance - pool_tokens;
+        if excess_tokens > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
                     ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.user_ata.to_account_info(),
-                        to: ctx.accounts.sol_vault.to_account_info(),
-                        authority: ctx.accounts.user.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: ctx.accounts.vault.to_account_info(),
+                        authority: contract.to_account_info(),
                     },
+                    &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
                 ),
-                total_tax,
+                excess_tokens,
             )?;
-            contract.vault_sol_balance += total_tax;
-            msg!("Transferred {} lamports (tax) to SafePump sol_vault from meme coin", total_tax);
+            contract.vault_token_balance = pool_tokens;
+            contract.total_supply = contract.total_supply.checked_sub(excess_tokens).ok_or(SafePumpError::MathError)?;
+            msg!("Burned {} vault tokens above the curve's closing price", excess_tokens);
+        }
 
-            if lp_tax > 0 {
-                token::transfer(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.user_ata.to_account_info(),
-                            to: ctx.accounts.lp_vault.to_account_info(),
-                            authority: ctx.accounts.user.to_account_info(),
-                        },
-                    ),
-                    lp_tax,
-                )?;
-                msg!("Transferred {} lamports to SafePump LP vault from meme coin", lp_tax);
-            }
+        let cpi_accounts = CreatePool {
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            token0_vault: ctx.accounts.vault.to_account_info(),
+            token1_vault: ctx.accounts.sol_vault.to_account_info(),
+            lp_mint: ctx.accounts.lp_mint.to_account_info(),
+            amm_config: ctx.accounts.amm_config.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+            observation_state: ctx.accounts.observation_state.to_account_info(),
+            create_pool_fee: ctx.accounts.create_pool_fee.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.raydium_program.to_account_info();
+        create_pool(
+            CpiContext::new_with_signer(
+                cpi_program,
+                cpi_accounts,
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+            ),
+        )?;
+        msg!(
+            "Migrated curve to Raydium: sol={} lamports, tokens={}",
+            contract.vault_sol_balance,
+            contract.vault_token_balance
+        );
+
+        // Burn LP tokens if specified
+        if contract.burn_percentage > 0 {
+            let burn_amount = ctx.accounts.lp_vault.amount * contract.burn_percentage as u64 / 100;
+            token_interface::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.lp_mint.to_account_info(),
+                        from: ctx.accounts.lp_vault.to_account_info(),
+                        authority: ctx.accounts.owner.to_account_info(),
+                    },
+                ),
+                burn_amount,
+            )?;
+            msg!("Burned {}% of LP tokens: {}", contract.burn_percentage, burn_amount);
         }
 
-        contract.total_swapped = contract.total_swapped.checked_add(amount).ok_or(SafePumpError::MathError)?;
-        contract.swap_count = contract.swap_count.checked_add(1).ok_or(SafePumpError::MathError)?;
+        curve.migrated = true;
+        let clock = Clock::get()?;
+        contract.bond_timestamp = clock.unix_timestamp;
+        contract.bond_slot = clock.slot;
+        contract.pool_vault = ctx.accounts.vault.key();
+        contract.pool_sol_vault = ctx.accounts.sol_vault.key();
+        contract.pool_state = ctx.accounts.pool_state.key();
+        contract.launch_stage = LaunchStage::Bonded;
+        Ok(())
+    }
+
+    pub fn initialize_presale(
+        ctx: Context<InitializePresale>,
+        merkle_root: [u8; 32],
+        start_timestamp: i64,
+        end_timestamp: i64,
+        hard_cap: u64,
+        token_amount: u64,
+        vesting_duration: i64,
+    ) -> Result<()> {
+        let contract = &mut ctx.accounts.contract;
+        contract.require_stage(LaunchStage::Allocated)?;
+        require!(end_timestamp > start_timestamp, SafePumpError::InvalidPresaleConfig);
+        require!(
+            end_timestamp - start_timestamp <= MAX_PRESALE_DURATION,
+            SafePumpError::InvalidPresaleConfig
+        );
+        require!(hard_cap > 0, SafePumpError::InvalidPresaleConfig);
+        require!(
+            token_amount > 0 && token_amount <= contract.vault_token_balance,
+            SafePumpError::InvalidPresaleConfig
+        );
+        require!(
+            (0..=MAX_VESTING_DURATION).contains(&vesting_duration),
+            SafePumpError::InvalidPresaleConfig
+        );
+
+        // Presale tokens are carved out of the pool vault
+        transfer_safepump(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.presale_escrow.to_account_info(),
+                    authority: contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            token_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        contract.vault_token_balance = contract.vault_token_balance.checked_sub(token_amount).ok_or(SafePumpError::MathError)?;
+        contract.presale_active = true;
 
+        let presale = &mut ctx.accounts.presale;
+        presale.contract = contract.key();
+        presale.escrow = ctx.accounts.presale_escrow.key();
+        presale.merkle_root = merkle_root;
+        presale.start_timestamp = start_timestamp;
+        presale.end_timestamp = end_timestamp;
+        presale.hard_cap = hard_cap;
+        presale.token_amount = token_amount;
+        presale.total_raised = 0;
+        presale.finalized = false;
+        presale.finalize_timestamp = 0;
+        presale.vesting_duration = vesting_duration;
+        presale.bump = ctx.bumps.presale;
+        msg!(
+            "Initialized presale: tokens={}, hard_cap={} lamports, window={}..{}",
+            token_amount,
+            hard_cap,
+            start_timestamp,
+            end_timestamp
+        );
         Ok(())
     }
 
-    pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
-        let reward_dist = &mut ctx.accounts.reward_distribution;
-        let badge_holders = &ctx.accounts.badge_holders;
+    pub fn contribute(
+        ctx: Context<Contribute>,
+        amount: u64,
+        max_contribution: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let presale = &mut ctx.accounts.presale;
         let clock = Clock::get()?;
+        // A presale only runs before the pool exists
+        require!(ctx.accounts.contract.launch_stage < LaunchStage::Bonded, SafePumpError::InvalidLaunchStage);
+        require!(!presale.finalized, SafePumpError::PresaleFinalized);
+        require!(
+            clock.unix_timestamp >= presale.start_timestamp && clock.unix_timestamp < presale.end_timestamp,
+            SafePumpError::PresaleNotOpen
+        );
+        require!(amount > 0, SafePumpError::InvalidAmount);
+        require!(proof.len() <= MAX_MERKLE_PROOF_LENGTH, SafePumpError::InvalidMerkleProof);
 
+        let user_key = ctx.accounts.user.key();
+        let leaf = keccak::hashv(&[user_key.as_ref(), &max_contribution.to_le_bytes()]).to_bytes();
         require!(
-            clock.unix_timestamp - reward_dist.last_distribution_timestamp >= REWARD_DISTRIBUTION_PERIOD,
-            SafePumpError::DistributionPeriodNotMet
+            verify_merkle_proof(&proof, presale.merkle_root, leaf),
+            SafePumpError::InvalidMerkleProof
         );
 
-        let mut remaining_swapper_rewards = [(Pubkey::default(), 0); MAX_BADGE_HOLDERS];
-        let mut new_count = 0;
-        for (user, amount) in reward_dist.swapper_rewards.iter() {
-            if amount > 0 {
-                invoke(
-                    &solana_program::system_instruction::transfer(
-                        &ctx.accounts.sol_vault.key(),
-                        user,
-                        *amount,
-                    ),
-                    &[
-                        ctx.accounts.sol_vault.to_account_info(),
-                        ctx.accounts.user.to_account_info(),
-                        ctx.accounts.system_program.to_account_info(),
-                    ],
-                )?;
-                msg!("Distributed {} lamports to swapper: {}", amount, user);
-            } else {
-                remaining_swapper_rewards[new_count] = (*user, *amount);
-                new_count += 1;
-            }
+        let receipt = &mut ctx.accounts.presale_receipt;
+        if receipt.presale == Pubkey::default() {
+            receipt.presale = presale.key();
+            receipt.wallet = user_key;
+            receipt.bump = ctx.bumps.presale_receipt;
         }
-        reward_dist.swapper_rewards = remaining_swapper_rewards;
-        reward_dist.swap_count = new_count as u64;
+        let contributed = receipt.contributed.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        require!(contributed <= max_contribution, SafePumpError::ExceedsMaxContribution);
+        let total_raised = presale.total_raised.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        require!(total_raised <= presale.hard_cap, SafePumpError::PresaleHardCapReached);
 
-        if badge_holders.holder_count > 0 && reward_dist.badge_rewards > 0 {
-            let reward_per_holder = reward_dist.badge_rewards
-                .checked_div(badge_holders.holder_count)
-                .ok_or(SafePumpError::MathError)?;
-            
-            for holder in badge_holders.holders.iter().take(badge_holders.holder_count as usize) {
-                if reward_per_holder > 0 && *holder != Pubkey::default() {
-                    invoke(
-                        &solana_program::system_instruction::transfer(
-                            &ctx.accounts.sol_vault.key(),
-                            holder,
-                            reward_per_holder,
-                        ),
-                        &[
-                            ctx.accounts.sol_vault.to_account_info(),
-                            ctx.accounts.system_program.to_account_info(),
-                        ],
-                    )?;
-                    msg!("Distributed {} lamports to badge holder: {}", reward_per_holder, holder);
-                }
-            }
-            reward_dist.badge_rewards = 0;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.wsol_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_wsol_ata.to_account_info(),
+                    mint: ctx.accounts.wsol_mint.to_account_info(),
+                    to: ctx.accounts.sol_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.wsol_mint.decimals,
+        )?;
+
+        receipt.contributed = contributed;
+        presale.total_raised = total_raised;
+        let contract = &mut ctx.accounts.contract;
+        contract.vault_sol_balance = contract.vault_sol_balance.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        msg!(
+            "Presale contribution: user={}, amount={}, total={}/{}",
+            user_key,
+            amount,
+            receipt.contributed,
+            max_contribution
+        );
+        Ok(())
+    }
+
+    pub fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
+        let presale = &mut ctx.accounts.presale;
+        let clock = Clock::get()?;
+        require!(ctx.accounts.contract.launch_stage < LaunchStage::Bonded, SafePumpError::InvalidLaunchStage);
+        require!(!presale.finalized, SafePumpError::PresaleFinalized);
+        require!(
+            clock.unix_timestamp >= presale.end_timestamp || presale.total_raised >= presale.hard_cap,
+            SafePumpError::PresaleNotEnded
+        );
+
+        let contract = &mut ctx.accounts.contract;
+        // Nothing raised, hand the tokens back to the pool vault
+        if presale.total_raised == 0 {
+            transfer_safepump(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.presale_escrow.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                        authority: contract.to_account_info(),
+                    },
+                    &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[contract.bump]]],
+                ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+                presale.token_amount,
+                ctx.accounts.mint.decimals,
+            )?;
+            contract.vault_token_balance = contract.vault_token_balance.checked_add(presale.token_amount).ok_or(SafePumpError::MathError)?;
+            msg!("Presale raised nothing, returned {} tokens to vault", presale.token_amount);
         }
 
-        reward_dist.last_distribution_timestamp = clock.unix_timestamp;
-        reward_dist.swap_count = 0;
-        msg!("Distributed rewards: swapper_rewards={}, badge_rewards={}", reward_dist.swap_count, reward_dist.badge_rewards);
+        presale.finalized = true;
+        presale.finalize_timestamp = clock.unix_timestamp;
+        contract.presale_active = false;
+        msg!("Finalized presale: raised {} lamports", presale.total_raised);
         Ok(())
     }
 
-    pub fn add_badge_holder(ctx: Context<AddBadgeHolder>) -> Result<()> {
-        let badge_holders = &mut ctx.accounts.badge_holders;
-        let user_key = ctx.accounts.user.key();
-        let index = badge_holders.buy_swap_count.iter().position(|(pubkey, _)| *pubkey == user_key);
+    pub fn claim_presale(ctx: Context<ClaimPresale>) -> Result<()> {
+        let presale = &ctx.accounts.presale;
+        let receipt = &mut ctx.accounts.presale_receipt;
+        let clock = Clock::get()?;
+        require!(presale.finalized, SafePumpError::PresaleNotEnded);
 
+        let claimable = presale
+            .unlocked_allocation(receipt.contributed, clock.unix_timestamp)?
+            .checked_sub(receipt.claimed)
+            .ok_or(SafePumpError::MathError)?;
+        require!(claimable > 0, SafePumpError::NothingToClaim);
+
+        transfer_safepump(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.presale_escrow.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_ata.to_account_info(),
+                    authority: ctx.accounts.contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[ctx.accounts.contract.bump]]],
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            claimable,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        receipt.claimed = receipt.claimed.checked_add(claimable).ok_or(SafePumpError::MathError)?;
+        msg!("Claimed {} presale tokens for {}", claimable, receipt.wallet);
+        Ok(())
+    }
+
+    pub fn lock_lp(ctx: Context<LockLp>, amount: u64, unlock_timestamp: i64, linear_release: bool) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        let clock = Clock::get()?;
+        require!(contract.launch_stage >= LaunchStage::Bonded, SafePumpError::NotBonded);
+        require!(amount > 0, SafePumpError::InvalidAmount);
         require!(
-            index.is_some() && badge_holders.buy_swap_count[index.unwrap()].1 >= BUY_SWAPS_FOR_BADGE,
-            SafePumpError::InsufficientBuySwaps
+            unlock_timestamp >= clock.unix_timestamp + MIN_LP_LOCK_DURATION
+                && unlock_timestamp <= clock.unix_timestamp + MAX_LP_LOCK_DURATION,
+            SafePumpError::InvalidLockDuration
         );
-        require!(
-            !badge_holders.holders.iter().any(|h| *h == user_key),
-            SafePumpError::AlreadyBadgeHolder
+
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.lp_vault.to_account_info(),
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.lp_lock_escrow.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.lp_mint.decimals,
+        )?;
+
+        let lp_lock = &mut ctx.accounts.lp_lock;
+        lp_lock.contract = contract.key();
+        lp_lock.owner = ctx.accounts.owner.key();
+        lp_lock.lp_mint = ctx.accounts.lp_mint.key();
+        lp_lock.escrow = ctx.accounts.lp_lock_escrow.key();
+        lp_lock.total_amount = amount;
+        lp_lock.withdrawn_amount = 0;
+        lp_lock.lock_timestamp = clock.unix_timestamp;
+        lp_lock.unlock_timestamp = unlock_timestamp;
+        lp_lock.linear_release = linear_release;
+        lp_lock.bump = ctx.bumps.lp_lock;
+        msg!(
+            "Locked {} LP tokens until {} (linear_release={})",
+            amount,
+            unlock_timestamp,
+            linear_release
         );
+        Ok(())
+    }
+
+    pub fn extend_lock(ctx: Context<ExtendLock>, new_unlock_timestamp: i64) -> Result<()> {
+        let lp_lock = &mut ctx.accounts.lp_lock;
+        require!(new_unlock_timestamp > lp_lock.unlock_timestamp, SafePumpError::InvalidLockDuration);
         require!(
-            badge_holders.holder_count < MAX_BADGE_HOLDERS as u64,
-            SafePumpError::BadgeHolderLimitReached
+            new_unlock_timestamp <= lp_lock.lock_timestamp + MAX_LP_LOCK_DURATION,
+            SafePumpError::InvalidLockDuration
         );
 
-        badge_holders.holders[badge_holders.holder_count as usize] = user_key;
-        badge_holders.buy_swap_count[index.unwrap()].1 = 0;
-        badge_holders.holder_count += 1;
-        msg!("Added badge holder: {}", user_key);
+        lp_lock.unlock_timestamp = new_unlock_timestamp;
+        msg!("Extended LP lock {} until {}", lp_lock.key(), new_unlock_timestamp);
         Ok(())
     }
-}
-
-#[derive(Accounts, Bumps)]
-pub struct InitializeContract<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + 1 + 8 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 1 + 1 + 4 + (4 + MAX_FRIENDS_WALLETS * 32) + (4 + MAX_FRIENDS_WALLETS * 8) + 8, // Updated for deployer_amount
-        seeds = [b"contract", owner.key().as_ref()],
-        bump
-    )]
-    pub contract: Account<'info, TokenContract>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub badge_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub swap_rewards_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub sol_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub owner_wsol_ata: Account<'info, TokenAccount>,
-    #[account(
-        init_if_needed,
-        payer = owner,
-        associated_token::mint = mint,
-        associated_token::authority = owner
-    )]
-    pub deployer_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub pool_state: AccountInfo<'info>,
-    #[account(mut)]
-    pub observation_state: AccountInfo<'info>,
-    #[account(mut)]
-    pub amm_config: AccountInfo<'info>,
-    #[account(mut)]
-    pub authority: AccountInfo<'info>,
-    #[account(mut)]
-    pub create_pool_fee: AccountInfo<'info>,
-    #[account(address = raydium_cp_swap::id())]
-    pub raydium_program: Program<'info, raydium_cp_swap::program::RaydiumCpSwap>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
 
-#[derive(Accounts, Bumps)]
-pub struct InitializeBadgeHolders<'info> {
-    #[account(
-        mut,
-        seeds = [b"contract", owner.key().as_ref()],
-        bump
-    )]
-    pub contract: Account<'info, TokenContract>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + (32 * MAX_BADGE_HOLDERS) + (40 * MAX_BADGE_HOLDERS) + 8 + 1,
-        seeds = [b"badge-holders", mint.key().as_ref()],
-        bump
-    )]
-    pub badge_holders: Account<'info, BadgeHolders>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+    pub fn withdraw_unlocked_lp(ctx: Context<WithdrawUnlockedLp>) -> Result<()> {
+        let lp_lock = &mut ctx.accounts.lp_lock;
+        let clock = Clock::get()?;
 
-#[derive(Accounts, Bumps)]
-pub struct RegisterMemeCoin<'info> {
-    #[account(
-        mut,
-        seeds = [b"contract", owner.key().as_ref()],
-        bump
-    )]
-    pub contract: Account<'info, TokenContract>,
-    #[account(
-        init_if_needed,
-        payer = deployer,
-        space = 8 + 4 + (64 * 1000) + 1,
-        seeds = [b"meme-coin-registry", contract.key().as_ref()],
-        bump
-    )]
-    pub meme_coin_registry: Account<'info, MemeCoinRegistry>,
-    #[account(mut)]
-    pub deployer: Signer<'info>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+        let withdrawable = lp_lock
+            .unlocked_amount(clock.unix_timestamp)?
+            .saturating_sub(lp_lock.withdrawn_amount);
+        require!(withdrawable > 0, SafePumpError::NothingToClaim);
 
-#[derive(Accounts, Bumps)]
-pub struct GlobalTaxSwap<'info> {
-    #[account(
+        let contract_key = lp_lock.contract;
+        let lp_mint_key = lp_lock.lp_mint;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.lp_lock_escrow.to_account_info(),
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: lp_lock.to_account_info(),
+                },
+                &[&[b"lp-lock", contract_key.as_ref(), lp_mint_key.as_ref(), &[lp_lock.bump]]],
+            ),
+            withdrawable,
+            ctx.accounts.lp_mint.decimals,
+        )?;
+
+        lp_lock.withdrawn_amount = lp_lock.withdrawn_amount.checked_add(withdrawable).ok_or(SafePumpError::MathError)?;
+        msg!(
+            "Withdrew {} unlocked LP tokens ({} of {} withdrawn)",
+            withdrawable,
+            lp_lock.withdrawn_amount,
+            lp_lock.total_amount
+        );
+        Ok(())
+    }
+
+    pub fn initialize_badge_registry(ctx: Context<InitializeBadgeRegistry>) -> Result<()> {
+        let badge_registry = &mut ctx.accounts.badge_registry;
+        badge_registry.holder_count = 0;
+        badge_registry.page_count = 0;
+        badge_registry.bump = ctx.bumps.badge_registry;
+        msg!("Initialized badge registry for mint: {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    pub fn register_meme_coin(ctx: Context<RegisterMemeCoin>, program_id: Pubkey, tax_schedule: TaxSchedule) -> Result<()> {
+        let registry = &mut ctx.accounts.meme_coin_registry;
+        let deployer = ctx.accounts.deployer.key();
+
+        require!(
+            !registry.meme_coins.iter().any(|(pid, _)| *pid == program_id),
+            SafePumpError::MemeCoinAlreadyRegistered
+        );
+        tax_schedule.validate()?;
+
+        registry.meme_coins[registry.meme_coin_count as usize] = (program_id, deployer);
+        registry.meme_coin_count += 1;
+        registry.bump = ctx.bumps.meme_coin_registry;
+
+        let meme_coin_tax = &mut ctx.accounts.meme_coin_tax;
+        meme_coin_tax.contract = ctx.accounts.contract.key();
+        meme_coin_tax.program_id = program_id;
+        meme_coin_tax.schedule = tax_schedule;
+        meme_coin_tax.bump = ctx.bumps.meme_coin_tax;
+        msg!(
+            "Registered meme coin: program_id={}, deployer={}, tax={} bp",
+            program_id,
+            deployer,
+            tax_schedule.total()
+        );
+        Ok(())
+    }
+
+    // Deployers may only lower each component of their coin's tax after registration
+    pub fn lower_meme_coin_tax(ctx: Context<LowerMemeCoinTax>, program_id: Pubkey, tax_schedule: TaxSchedule) -> Result<()> {
+        let registry = &ctx.accounts.meme_coin_registry;
+        let index = registry.index_of(program_id).ok_or(SafePumpError::MemeCoinNotRegistered)?;
+        require!(
+            registry.meme_coins[index].1 == ctx.accounts.deployer.key(),
+            SafePumpError::NotMemeCoinDeployer
+        );
+
+        let meme_coin_tax = &mut ctx.accounts.meme_coin_tax;
+        let current = meme_coin_tax.schedule;
+        tax_schedule.check_lowered_from(&current)?;
+
+        meme_coin_tax.schedule = tax_schedule;
+        msg!(
+            "Lowered meme coin tax: program_id={}, {} bp -> {} bp",
+            program_id,
+            current.total(),
+            tax_schedule.total()
+        );
+        Ok(())
+    }
+
+    pub fn global_tax_swap(
+        ctx: Context<GlobalTaxSwap>,
+        amount: u64,
+        is_buy: bool,
+        meme_program_id: Pubkey,
+        minimum_amount_out: u64,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let (is_safepump_swap, tax_schedule) = ctx.accounts.validate_swap(meme_program_id, deadline, clock.unix_timestamp)?;
+        ctx.accounts.update_buy_cap()?;
+
+        // Enforce buy cap
+        let contract = &ctx.accounts.contract;
+        let max_buy_amount = contract.total_supply * contract.buy_cap_percentage / 10_000;
+        require!(amount <= max_buy_amount, SafePumpError::ExceedsMaxBuy);
+
+        let sells_safepump = is_safepump_swap && !is_buy;
+        let charges = SwapCharges::exact_in(tax_schedule, amount, sells_safepump)?;
+        let lp_amount = charges.swap_amount;
+        let launch_tax_rate = ctx.accounts.launch_tax_rate(is_safepump_swap, clock.unix_timestamp);
+
+        // Token and launch tax leave the wallet with a SafePump sale, so they count as volume sold
+        let volume = if sells_safepump {
+            charges.sell_debit(amount, basis_points(amount, launch_tax_rate)?)?
+        } else {
+            amount
+        };
+
+        // Enforce sell lock if buy cap is below the unlock threshold
+        if !is_buy {
+            let balance = ctx.accounts.user_safepump_ata.amount;
+            ctx.accounts.enforce_sell_limits(volume, balance, clock.unix_timestamp)?;
+        } else if is_safepump_swap {
+            ctx.accounts.enforce_slot_limits(amount, clock.slot)?;
+        }
+        // Sell tax is held in tokens, so its badge share funds rewards when convert_token_tax runs
+        if !sells_safepump {
+            ctx.accounts.record_swap_rewards(is_buy, charges.swapper_tax, charges.badge_tax)?;
+        }
+
+        // Slippage is measured on the destination ATA, not on Raydium's own accounting
+        let (_, destination_before) = ctx.accounts.swap_balances(sells_safepump);
+        let mut swap_performed = true;
+
+        if is_safepump_swap {
+            if is_buy {
+                ctx.accounts.swap_base_in(ctx.remaining_accounts, false, lp_amount, minimum_amount_out)?;
+                msg!("Performed SafePump swap: amount={} lamports to LP", lp_amount);
+            } else {
+                ctx.accounts.swap_base_in(ctx.remaining_accounts, true, amount, minimum_amount_out)?;
+                msg!("Performed SafePump sell: amount={} tokens", amount);
+            }
+        } else {
+            let meme_bonded = ctx.accounts.meme_coin_data.load()?.bond_timestamp > 0;
+            if meme_bonded {
+                ctx.accounts.swap_base_in(ctx.remaining_accounts, false, lp_amount, minimum_amount_out)?;
+                msg!("Performed meme coin swap: amount={} lamports to meme coin LP", lp_amount);
+            } else {
+                token_interface::transfer_checked(
+                    CpiContext::new(
+                        ctx.accounts.wsol_token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.user_ata.to_account_info(),
+                            mint: ctx.accounts.wsol_mint.to_account_info(),
+                            to: ctx.accounts.vault.to_account_info(),
+                            authority: ctx.accounts.user.to_account_info(),
+                        },
+                    ),
+                    lp_amount,
+                    ctx.accounts.wsol_mint.decimals,
+                )?;
+                swap_performed = false;
+                msg!("Transferred {} lamports to pre-bonded meme coin vault", lp_amount);
+            }
+        }
+
+        ctx.accounts.collect_tax(ctx.remaining_accounts, sells_safepump, charges.total_tax, charges.lp_tax)?;
+
+        if swap_performed {
+            ctx.accounts.user_ata.reload()?;
+            ctx.accounts.user_safepump_ata.reload()?;
+            let (_, destination_after) = ctx.accounts.swap_balances(sells_safepump);
+            let amount_out = destination_after.saturating_sub(destination_before);
+
+            // Launch tax comes out of the SafePump tokens received on buys and sent on sells
+            let launch_tax = basis_points(if is_buy { amount_out } else { amount }, launch_tax_rate)?;
+            let net_amount_out = if is_buy { amount_out - launch_tax } else { amount_out };
+            require!(net_amount_out >= minimum_amount_out, SafePumpError::SlippageExceeded);
+            if is_safepump_swap && is_buy {
+                ctx.accounts.enforce_max_wallet(destination_after - launch_tax)?;
+            }
+            ctx.accounts.collect_launch_tax(ctx.remaining_accounts, launch_tax)?;
+            msg!("Swap output: {} (minimum {})", net_amount_out, minimum_amount_out);
+        }
+
+        let user_swap_data = &mut ctx.accounts.user_swap_data;
+        user_swap_data.bump = ctx.bumps.user_swap_data;
+        user_swap_data.record_trade(is_buy, volume, clock.unix_timestamp)?;
+        ctx.accounts.badge_progress.bump = ctx.bumps.badge_progress;
+        ctx.accounts.user_rewards.bump = ctx.bumps.user_rewards;
+        ctx.accounts.reward_distribution.bump = ctx.bumps.reward_distribution;
+
+        let contract = &mut ctx.accounts.contract;
+        contract.total_swapped = contract.total_swapped.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        contract.swap_count = contract.swap_count.checked_add(1).ok_or(SafePumpError::MathError)?;
+
+        Ok(())
+    }
+
+    pub fn global_tax_swap_exact_out(
+        ctx: Context<GlobalTaxSwap>,
+        amount_out: u64,
+        max_amount_in: u64,
+        is_buy: bool,
+        meme_program_id: Pubkey,
+        deadline: Option<i64>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(amount_out > 0, SafePumpError::InvalidAmount);
+        let (is_safepump_swap, tax_schedule) = ctx.accounts.validate_swap(meme_program_id, deadline, clock.unix_timestamp)?;
+        if !is_safepump_swap {
+            // Pre-bond meme coins have no pool to quote an exact output against
+            require!(ctx.accounts.meme_coin_data.load()?.bond_timestamp > 0, SafePumpError::NotBonded);
+        }
+        ctx.accounts.update_buy_cap()?;
+
+        // The exact input is only known after the swap, sell limits are checked against it below
+        let sell_balance = ctx.accounts.user_safepump_ata.amount;
+
+        // Launch tax is paid in SafePump tokens, so it only shares the input budget on sells
+        let launch_tax_rate = ctx.accounts.launch_tax_rate(is_safepump_swap, clock.unix_timestamp);
+        let input_tax_rate = if is_buy || !is_safepump_swap {
+            tax_schedule.total()
+        } else {
+            tax_schedule.token_tax_rate() + launch_tax_rate
+        };
+
+        // Leave room for the tax inside the user's maximum
+        let swap_max_in = (max_amount_in as u128)
+            .checked_mul(10_000)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(10_000 + input_tax_rate as u128)
+            .ok_or(SafePumpError::MathError)? as u64;
+
+        // Buys request enough extra tokens to cover the launch tax taken from the output
+        let swap_amount_out = if is_buy && launch_tax_rate > 0 {
+            ((amount_out as u128 * 10_000).div_ceil(10_000 - launch_tax_rate as u128)) as u64
+        } else {
+            amount_out
+        };
+        if is_buy {
+            // The buy cap applies to the gross tokens pulled from the pool, launch tax included
+            let contract = &ctx.accounts.contract;
+            let max_buy_amount = contract.total_supply * contract.buy_cap_percentage / 10_000;
+            require!(swap_amount_out <= max_buy_amount, SafePumpError::ExceedsMaxBuy);
+        }
+
+        let sells_safepump = is_safepump_swap && !is_buy;
+        let (source_before, destination_before) = ctx.accounts.swap_balances(sells_safepump);
+        ctx.accounts.swap_base_out(ctx.remaining_accounts, sells_safepump, swap_max_in, swap_amount_out)?;
+        ctx.accounts.user_ata.reload()?;
+        ctx.accounts.user_safepump_ata.reload()?;
+        let (source_after, destination_after) = ctx.accounts.swap_balances(sells_safepump);
+
+        let amount_in = source_before.checked_sub(source_after).ok_or(SafePumpError::MathError)?;
+        let received = destination_after.saturating_sub(destination_before);
+        let launch_tax = basis_points(if is_buy { received } else { amount_in }, launch_tax_rate)?;
+        let net_received = if is_buy { received.saturating_sub(launch_tax) } else { received };
+        require!(net_received >= amount_out, SafePumpError::SlippageExceeded);
+        if is_safepump_swap && is_buy {
+            ctx.accounts.enforce_max_wallet(destination_after.saturating_sub(launch_tax))?;
+        }
+
+        // Tax is charged on what the swap actually consumed
+        let charges = SwapCharges::on(tax_schedule, amount_in, sells_safepump)?;
+        let total_tax = charges.total_tax;
+        let input_tax = if is_buy { total_tax } else { total_tax + launch_tax };
+        require!(
+            amount_in.checked_add(input_tax).ok_or(SafePumpError::MathError)? <= max_amount_in,
+            SafePumpError::SlippageExceeded
+        );
+        // Volume is the gross input including tax, matching global_tax_swap where the tax comes out of `amount`
+        let volume = amount_in + input_tax;
+        if is_buy && is_safepump_swap {
+            ctx.accounts.enforce_slot_limits(volume, clock.slot)?;
+        } else if !is_buy {
+            ctx.accounts.enforce_sell_limits(volume, sell_balance, clock.unix_timestamp)?;
+        }
+        if !sells_safepump {
+            ctx.accounts.record_swap_rewards(is_buy, charges.swapper_tax, charges.badge_tax)?;
+        }
+        ctx.accounts.collect_tax(ctx.remaining_accounts, sells_safepump, total_tax, charges.lp_tax)?;
+        ctx.accounts.collect_launch_tax(ctx.remaining_accounts, launch_tax)?;
+        msg!(
+            "Performed exact-out swap: received={}, spent={}, tax={}, launch_tax={}",
+            net_received,
+            amount_in,
+            total_tax,
+            launch_tax
+        );
+
+        let user_swap_data = &mut ctx.accounts.user_swap_data;
+        user_swap_data.bump = ctx.bumps.user_swap_data;
+        user_swap_data.record_trade(is_buy, volume, clock.unix_timestamp)?;
+        ctx.accounts.badge_progress.bump = ctx.bumps.badge_progress;
+        ctx.accounts.user_rewards.bump = ctx.bumps.user_rewards;
+        ctx.accounts.reward_distribution.bump = ctx.bumps.reward_distribution;
+
+        let contract = &mut ctx.accounts.contract;
+        contract.total_swapped = contract.total_swapped.checked_add(amount_in).ok_or(SafePumpError::MathError)?;
+        contract.swap_count = contract.swap_count.checked_add(1).ok_or(SafePumpError::MathError)?;
+
+        Ok(())
+    }
+
+    // Simulation-only preview of global_tax_swap, returned to clients as Borsh return data
+    pub fn quote_swap(ctx: Context<QuoteSwap>, amount: u64, is_buy: bool, meme_program_id: Pubkey) -> Result<SwapQuote> {
+        let contract = &ctx.accounts.contract;
+        let config = &ctx.accounts.launch_config;
+        let now = Clock::get()?.unix_timestamp;
+        let is_safepump_swap = meme_program_id == contract.mint;
+        let tax_schedule = swap_tax_schedule(
+            &contract.key(),
+            is_safepump_swap,
+            meme_program_id,
+            &ctx.accounts.meme_coin_registry,
+            ctx.accounts.meme_coin_tax.as_deref(),
+        )?;
+        check_swap_pool(
+            contract,
+            is_safepump_swap,
+            &ctx.accounts.pool_state.key(),
+            &ctx.accounts.vault.key(),
+            &ctx.accounts.sol_vault.key(),
+        )?;
+        // Reserves must be read from the quoted pool's own vaults
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let pool_vaults = [pool_state.token_0_vault, pool_state.token_1_vault];
+        require!(
+            ctx.accounts.vault.key() != ctx.accounts.sol_vault.key()
+                && pool_vaults.contains(&ctx.accounts.vault.key())
+                && pool_vaults.contains(&ctx.accounts.sol_vault.key()),
+            SafePumpError::InvalidPoolVault
+        );
+        drop(pool_state);
+        let pool_sol_amount = ctx.accounts.sol_vault.amount;
+        let pool_token_amount = ctx.accounts.vault.amount;
+
+        let projection = BuyCapProjection::new(contract, config, pool_sol_amount, pool_token_amount)?;
+        let charges = SwapCharges::exact_in(tax_schedule, amount, is_safepump_swap && !is_buy)?;
+
+        // Buys route amount minus tax into the pool, sells route the full amount and pay tax on top
+        let (reserve_in, reserve_out) = if is_buy {
+            (pool_sol_amount, pool_token_amount)
+        } else {
+            (pool_token_amount, pool_sol_amount)
+        };
+        let expected_amount_out = constant_product_amount_out(
+            charges.swap_amount,
+            reserve_in,
+            reserve_out,
+            ctx.accounts.amm_config.trade_fee_rate,
+        )?;
+        let launch_tax_rate = if is_safepump_swap { config.launch_tax_rate(contract.bond_timestamp, now) } else { 0 };
+        let launch_tax = basis_points(if is_buy { expected_amount_out } else { amount }, launch_tax_rate)?;
+        let (expected_amount_out, total_cost) = if is_buy {
+            (expected_amount_out - launch_tax, amount)
+        } else {
+            (expected_amount_out, if is_safepump_swap { charges.sell_debit(amount, launch_tax)? } else { amount })
+        };
+
+        let balance = ctx.accounts.user_safepump_ata.amount;
+        let (max_sell_amount, sell_window_resets_in) = match &ctx.accounts.user_swap_data {
+            Some(data) => (
+                data.sell_allowance(balance, config.max_sell_percent, config.sell_cooldown, now),
+                data.window_resets_in(config.sell_cooldown, now),
+            ),
+            None => (basis_points(balance, config.max_sell_percent)?, 0),
+        };
+        let anti_sniper_remaining = if contract.swap_count == 0 {
+            (contract.bond_timestamp + config.anti_sniper_cooldown - now).max(0)
+        } else {
+            0
+        };
+
+        Ok(SwapQuote {
+            amount_in: amount,
+            swap_amount: charges.swap_amount,
+            expected_amount_out,
+            total_cost,
+            total_tax: charges.total_tax,
+            lp_tax: charges.lp_tax,
+            swapper_tax: charges.swapper_tax,
+            badge_tax: charges.badge_tax,
+            launch_tax_rate,
+            launch_tax,
+            buy_cap_percentage: projection.buy_cap_percentage,
+            max_buy_amount: projection.max_buy_amount(contract.total_supply),
+            max_wallet_amount: config.max_wallet_amount(contract.total_supply, projection.liquidity_threshold_index)?,
+            market_cap: projection.market_cap,
+            sell_lock_active: projection.sell_lock_active,
+            max_sell_amount,
+            sell_window_resets_in,
+            anti_sniper_remaining,
+        })
+    }
+
+    // Owner crank swapping accumulated sell-side token tax to SOL through the bonded Raydium pool
+    pub fn convert_token_tax(ctx: Context<ConvertTokenTax>, amount: u64, minimum_amount_out: u64) -> Result<()> {
+        let contract = &ctx.accounts.contract;
+        contract.require_stage(LaunchStage::Finalized)?;
+        require!(amount > 0 && amount <= contract.token_tax_balance, SafePumpError::InvalidAmount);
+        require!(minimum_amount_out > 0, SafePumpError::SlippageExceeded);
+
+        let sol_before = ctx.accounts.tax_sol_vault.amount;
+        let signer_seeds: &[&[u8]] = &[b"contract", ctx.accounts.owner.key.as_ref(), &[contract.bump]];
+        let cpi_accounts = raydium_cp_swap::cpi::accounts::SwapBaseIn {
+            pool_state: ctx.accounts.pool_state.to_account_info(),
+            user_source_token: ctx.accounts.token_tax_vault.to_account_info(),
+            user_destination_token: ctx.accounts.tax_sol_vault.to_account_info(),
+            token_0_vault: ctx.accounts.vault.to_account_info(),
+            token_1_vault: ctx.accounts.sol_vault.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            remaining_accounts: ctx.remaining_accounts.to_vec(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.raydium_program.to_account_info(),
+            cpi_accounts,
+            &[signer_seeds],
+        );
+        raydium_cp_swap::cpi::swap_base_in(cpi_ctx, SwapBaseInput { amount, minimum_amount_out })?;
+
+        ctx.accounts.tax_sol_vault.reload()?;
+        let sol_out = ctx.accounts.tax_sol_vault.amount.saturating_sub(sol_before);
+        require!(sol_out >= minimum_amount_out, SafePumpError::SlippageExceeded);
+
+        // Sellers keep their swapper share, so the SOL splits between LP and badge shares in schedule proportion
+        let schedule = TaxSchedule::PROTOCOL;
+        let lp_share = (sol_out as u128)
+            .checked_mul(schedule.lp_tax as u128)
+            .ok_or(SafePumpError::MathError)?
+            / (schedule.lp_tax as u128 + schedule.badge_reward_tax as u128);
+        let lp_share = lp_share as u64;
+        let badge_share = sol_out - lp_share;
+        let wsol_decimals = ctx.accounts.wsol_mint.decimals;
+        for (to, share) in [
+            (ctx.accounts.sol_vault.to_account_info(), lp_share),
+            (ctx.accounts.reward_vault.to_account_info(), badge_share),
+        ] {
+            if share > 0 {
+                token_interface::transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.wsol_token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.tax_sol_vault.to_account_info(),
+                            mint: ctx.accounts.wsol_mint.to_account_info(),
+                            to,
+                            authority: ctx.accounts.contract.to_account_info(),
+                        },
+                        &[signer_seeds],
+                    ),
+                    share,
+                    wsol_decimals,
+                )?;
+            }
+        }
+        ctx.accounts.reward_distribution.accrue_badge_rewards(badge_share)?;
+        ctx.accounts.reward_distribution.bump = ctx.bumps.reward_distribution;
+
+        let contract = &mut ctx.accounts.contract;
+        contract.token_tax_balance -= amount;
+        contract.token_tax_converted = contract.token_tax_converted.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        contract.vault_sol_balance = contract.vault_sol_balance.checked_add(lp_share).ok_or(SafePumpError::MathError)?;
+        msg!(
+            "Converted {} tokens of tax to {} lamports: lp={}, badge={}",
+            amount,
+            sol_out,
+            lp_share,
+            badge_share
+        );
+        Ok(())
+    }
+
+    // Lets wallets that never used global_tax_swap sell directly against the pool
+    pub fn initialize_user_swap_data(ctx: Context<InitializeUserSwapData>) -> Result<()> {
+        let user_swap_data = &mut ctx.accounts.user_swap_data;
+        user_swap_data.last_sell_timestamp = 0;
+        user_swap_data.bump = ctx.bumps.user_swap_data;
+        msg!("Initialized swap data for {}", ctx.accounts.user.key());
+        Ok(())
+    }
+
+    // Grows a UserSwapData created by an older program version to the current layout, new fields start zeroed
+    pub fn migrate_user_swap_data(ctx: Context<MigrateUserSwapData>) -> Result<()> {
+        let info = ctx.accounts.user_swap_data.to_account_info();
+        let old_len = info.data_len();
+        require!(old_len < USER_SWAP_DATA_SPACE, SafePumpError::AlreadyMigrated);
+
+        let rent_shortfall = Rent::get()?
+            .minimum_balance(USER_SWAP_DATA_SPACE)
+            .saturating_sub(info.lamports());
+        if rent_shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                rent_shortfall,
+            )?;
+        }
+        info.realloc(USER_SWAP_DATA_SPACE, true)?;
+
+        // Older versions never stored the bump, fill it in while the account is open
+        let mut user_swap_data = UserSwapData::try_deserialize(&mut &info.data.borrow()[..])?;
+        user_swap_data.bump = ctx.bumps.user_swap_data;
+        user_swap_data.try_serialize(&mut &mut info.data.borrow_mut()[..])?;
+        msg!("Migrated swap data for {}: {} -> {} bytes", ctx.accounts.user.key(), old_len, USER_SWAP_DATA_SPACE);
+        Ok(())
+    }
+
+    // Called by the transfer hook for transfers out of a wallet that bypass the swap instructions
+    pub fn record_hook_sell(ctx: Context<RecordHookSell>, amount: u64, balance_before: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        check_sell_limits(
+            &ctx.accounts.contract,
+            &ctx.accounts.launch_config,
+            &mut ctx.accounts.user_swap_data,
+            amount,
+            balance_before,
+            now,
+        )?;
+        ctx.accounts.user_swap_data.record_trade(false, amount, now)?;
+        msg!("Recorded direct pool sell: seller={}, amount={}", ctx.accounts.seller.key(), amount);
+        Ok(())
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        let reward_dist = &mut ctx.accounts.reward_distribution;
+        let user_rewards = &mut ctx.accounts.user_rewards;
+        let user_key = ctx.accounts.user.key();
+
+        // Badge rewards only pay out while the user still holds their badge token
+        let has_weight = user_rewards.weight > 0;
+        let badge_rewards = user_rewards.settle(reward_dist.acc_reward_per_share)?;
+        let keeps_badge = if has_weight {
+            let badge_token_account = ctx.accounts.badge_token_account.as_ref().ok_or(SafePumpError::BadgeAccountRequired)?;
+            holds_badge(badge_token_account, &user_key, &ctx.accounts.mint.key())?
+        } else {
+            true
+        };
+        if badge_rewards > 0 && !keeps_badge {
+            user_rewards.pending_rewards -= badge_rewards;
+            reward_dist.unallocated_badge_rewards = reward_dist.unallocated_badge_rewards
+                .checked_add(badge_rewards)
+                .ok_or(SafePumpError::MathError)?;
+            msg!("Forfeited {} lamports of badge rewards without a badge token: {}", badge_rewards, user_key);
+        }
+
+        let amount = user_rewards.pending_rewards;
+        require!(amount > 0, SafePumpError::NoRewardsToClaim);
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.wsol_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    mint: ctx.accounts.wsol_mint.to_account_info(),
+                    to: ctx.accounts.user_wsol_ata.to_account_info(),
+                    authority: ctx.accounts.contract.to_account_info(),
+                },
+                &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[ctx.accounts.contract.bump]]],
+            ),
+            amount,
+            ctx.accounts.wsol_mint.decimals,
+        )?;
+
+        user_rewards.pending_rewards = 0;
+        user_rewards.claimed_rewards = user_rewards.claimed_rewards.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        reward_dist.total_claimed = reward_dist.total_claimed.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        msg!("Claimed {} lamports of rewards: {}", amount, user_key);
+        Ok(())
+    }
+
+    pub fn add_badge_holder(ctx: Context<AddBadgeHolder>, page: u64, uri: String) -> Result<()> {
+        validate_metadata(None, None, Some(&uri))?;
+        let badge_registry = &mut ctx.accounts.badge_registry;
+        let badge_page = &mut ctx.accounts.badge_page;
+        let badge_progress = &mut ctx.accounts.badge_progress;
+        let user_key = ctx.accounts.user.key();
+        let tier = BadgeTier::qualified(badge_progress, &ctx.accounts.user_swap_data, Clock::get()?.unix_timestamp);
+
+        require!(tier != BadgeTier::None, SafePumpError::InsufficientBuySwaps);
+        require!(!badge_progress.is_holder, SafePumpError::AlreadyBadgeHolder);
+        // Pages are created in order; any existing page with a free slot can take the holder
+        require!(page <= badge_registry.page_count, SafePumpError::InvalidBadgePage);
+        if page == badge_registry.page_count {
+            badge_page.page = page;
+            badge_page.bump = ctx.bumps.badge_page;
+            badge_registry.page_count += 1;
+        }
+        badge_page.insert(user_key, tier)?;
+        badge_progress.is_holder = true;
+        badge_progress.page = page;
+        badge_progress.sell_volume_at_award = ctx.accounts.user_swap_data.sell_volume;
+        badge_registry.holder_count = badge_registry.holder_count.checked_add(1).ok_or(SafePumpError::MathError)?;
+        ctx.accounts.reward_distribution.set_weight(&mut ctx.accounts.user_rewards, tier.reward_weight())?;
+
+        ctx.accounts.mint_badge(ctx.bumps.badge_mint, tier, uri)?;
+        msg!("Added {:?} badge holder: {}, page={}, badge_mint={}", tier, user_key, page, ctx.accounts.badge_mint.key());
+        Ok(())
+    }
+
+    pub fn upgrade_badge(ctx: Context<UpgradeBadge>) -> Result<()> {
+        let badge_page = &mut ctx.accounts.badge_page;
+        let user_key = ctx.accounts.user.key();
+        let index = badge_page.index_of(&user_key).ok_or(SafePumpError::NotBadgeHolder)?;
+        let current = badge_page.tiers[index];
+        let tier = BadgeTier::qualified(&ctx.accounts.badge_progress, &ctx.accounts.user_swap_data, Clock::get()?.unix_timestamp);
+        require!(tier > current, SafePumpError::BadgeTierNotReached);
+
+        badge_page.tiers[index] = tier;
+        ctx.accounts.reward_distribution.set_weight(&mut ctx.accounts.user_rewards, tier.reward_weight())?;
+
+        update_badge_metadata(
+            &ctx.accounts.token_2022_program.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.user.to_account_info(),
+            &ctx.accounts.badge_mint.to_account_info(),
+            &ctx.accounts.contract.to_account_info(),
+            &[b"contract", ctx.accounts.owner.key().as_ref(), &[ctx.accounts.contract.bump]],
+            vec![(Field::Name, tier.badge_name().to_string())],
+        )?;
+        msg!("Upgraded badge holder {} from {:?} to {:?}", user_key, current, tier);
+        Ok(())
+    }
+
+    pub fn revoke_badge(ctx: Context<RevokeBadge>) -> Result<()> {
+        let badge_registry = &mut ctx.accounts.badge_registry;
+        let badge_page = &mut ctx.accounts.badge_page;
+        let badge_progress = &mut ctx.accounts.badge_progress;
+        let holder_key = ctx.accounts.holder.key();
+        require!(badge_page.index_of(&holder_key).is_some(), SafePumpError::NotBadgeHolder);
+
+        // Anyone can crank this once the holder breaks a maintenance rule
+        let badge_balance = token_balance(&ctx.accounts.badge_token_account)?;
+        let reason = if badge_balance == 0 {
+            BadgeRevocation::MissingBadge
+        } else {
+            ctx.accounts.launch_config
+                .badge_revocation(
+                    &ctx.accounts.user_swap_data,
+                    badge_progress.sell_volume_at_award,
+                    token_balance(&ctx.accounts.holder_safepump_ata)?,
+                    Clock::get()?.unix_timestamp,
+                )?
+                .ok_or(SafePumpError::BadgeStillValid)?
+        };
+
+        // The contract is the badge's permanent delegate, so it can burn without the holder signing
+        if badge_balance > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_2022_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.badge_mint.to_account_info(),
+                        from: ctx.accounts.badge_token_account.to_account_info(),
+                        authority: ctx.accounts.contract.to_account_info(),
+                    },
+                    &[&[b"contract", ctx.accounts.owner.key().as_ref(), &[ctx.accounts.contract.bump]]],
+                ),
+                badge_balance,
+            )?;
+        }
+
+        // Rewards accrued up to now stay claimable; the holder stops earning from here
+        ctx.accounts.reward_distribution.set_weight(&mut ctx.accounts.user_rewards, 0)?;
+
+        // Free the page slot for the next eligible trader; the emptied badge mint and ATA are
+        // kept so add_badge_holder can mint into them again if the wallet requalifies
+        badge_registry.holder_count -= 1;
+        badge_page.remove(&holder_key)?;
+        badge_progress.is_holder = false;
+        badge_progress.sell_volume_at_award = 0;
+        msg!("Revoked badge: holder={}, badge_mint={}, reason={:?}", holder_key, ctx.accounts.badge_mint.key(), reason);
+        Ok(())
+    }
+}
+
+#[derive(Accounts, Bumps)]
+pub struct InitializeLaunchConfig<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + (8 * LIQUIDITY_TIERS) + (8 * LIQUIDITY_TIERS) + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + (8 * LIQUIDITY_TIERS) + 8 + 8 + 8 + 1,
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct InitializeContract<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = TokenContract::SPACE,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    // Launch mints are created as Token-2022 so they can carry metadata and the sell-rule transfer hook
+    #[account(
+        init,
+        payer = owner,
+        mint::decimals = TOKEN_DECIMALS,
+        mint::authority = contract,
+        mint::freeze_authority = contract,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = contract,
+        extensions::metadata_pointer::metadata_address = mint,
+        extensions::transfer_hook::authority = contract,
+        extensions::transfer_hook::program_id = TRANSFER_HOOK_PROGRAM_ID
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct UpdateMetadata<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct MintAllocations<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = contract,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = contract,
+        token::token_program = token_program,
+        seeds = [b"vesting-escrow", mint.key().as_ref()],
+        bump
+    )]
+    pub vesting_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct FundVaults<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint,
+        has_one = launch_config
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub badge_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub swap_rewards_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = wsol_mint, token::token_program = wsol_token_program)]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = wsol_mint, token::token_program = wsol_token_program)]
+    pub owner_wsol_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = contract,
+        token::token_program = token_program,
+        seeds = [b"token-tax-vault", contract.key().as_ref()],
+        bump
+    )]
+    pub token_tax_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = WSOL_MINT)]
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub wsol_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct BondToRaydium<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = launch_config
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub pool_state: AccountInfo<'info>,
+    #[account(mut)]
+    pub observation_state: AccountInfo<'info>,
+    #[account(mut)]
+    pub amm_config: AccountInfo<'info>,
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub create_pool_fee: AccountInfo<'info>,
+    #[account(address = raydium_cp_swap::id())]
+    pub raydium_program: Program<'info, raydium_cp_swap::program::RaydiumCpSwap>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct FinalizeLaunch<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts, Bumps)]
+#[instruction(beneficiary: Pubkey)]
+pub struct CreateVestingSchedule<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1,
+        seeds = [b"vesting", contract.key().as_ref(), beneficiary.as_ref()],
+        bump
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"vesting", contract.key().as_ref(), beneficiary.key().as_ref()],
+        bump = vesting_schedule.bump,
+        has_one = contract,
+        has_one = beneficiary
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+    #[account(
+        mut,
+        seeds = [b"vesting-escrow", mint.key().as_ref()],
+        bump,
+        constraint = vesting_escrow.key() == contract.vesting_escrow @ SafePumpError::InvalidVestingEscrow
+    )]
+    pub vesting_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct InitializeBondingCurve<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1,
+        seeds = [b"bonding-curve", mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        token::mint = mint,
+        token::authority = contract
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        token::mint = WSOL_MINT,
+        token::authority = contract
+    )]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct TradeOnCurve<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(
+        mut,
+        seeds = [b"bonding-curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+        has_one = contract,
+        has_one = vault,
+        has_one = sol_vault
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = wsol_mint,
+        token::authority = user,
+        token::token_program = wsol_token_program
+    )]
+    pub user_wsol_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = WSOL_MINT)]
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub wsol_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct MigrateToRaydium<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"bonding-curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+        has_one = contract,
+        has_one = vault,
+        has_one = sol_vault
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(mut, address = contract.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub pool_state: AccountInfo<'info>,
+    #[account(mut)]
+    pub observation_state: AccountInfo<'info>,
+    #[account(mut)]
+    pub amm_config: AccountInfo<'info>,
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub create_pool_fee: AccountInfo<'info>,
+    #[account(address = raydium_cp_swap::id())]
+    pub raydium_program: Program<'info, raydium_cp_swap::program::RaydiumCpSwap>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct InitializePresale<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1,
+        seeds = [b"presale", contract.key().as_ref()],
+        bump
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = contract,
+        token::token_program = token_program,
+        seeds = [b"presale-escrow", presale.key().as_ref()],
+        bump
+    )]
+    pub presale_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = contract,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct Contribute<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"presale", contract.key().as_ref()],
+        bump = presale.bump,
+        has_one = contract
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 32 + 32 + 8 + 8 + 1,
+        seeds = [b"presale-receipt", presale.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub presale_receipt: Account<'info, PresaleReceipt>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = wsol_mint,
+        token::authority = user,
+        token::token_program = wsol_token_program
+    )]
+    pub user_wsol_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = wsol_mint,
+        token::authority = contract,
+        token::token_program = wsol_token_program
+    )]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = WSOL_MINT)]
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+    pub wsol_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct FinalizePresale<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"presale", contract.key().as_ref()],
+        bump = presale.bump,
+        has_one = contract
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        constraint = presale_escrow.key() == presale.escrow @ SafePumpError::InvalidPresaleConfig
+    )]
+    pub presale_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = contract,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct ClaimPresale<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
+    #[account(
+        seeds = [b"presale", contract.key().as_ref()],
+        bump = presale.bump,
+        has_one = contract
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        seeds = [b"presale-receipt", presale.key().as_ref(), user.key().as_ref()],
+        bump = presale_receipt.bump
+    )]
+    pub presale_receipt: Account<'info, PresaleReceipt>,
+    #[account(
+        mut,
+        constraint = presale_escrow.key() == presale.escrow @ SafePumpError::InvalidPresaleConfig
+    )]
+    pub presale_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_ata: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct LockLp<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1,
+        seeds = [b"lp-lock", contract.key().as_ref(), lp_mint.key().as_ref()],
+        bump
+    )]
+    pub lp_lock: Account<'info, LpLock>,
+    #[account(
+        init,
+        payer = owner,
+        token::mint = lp_mint,
+        token::authority = lp_lock,
+        token::token_program = token_program,
+        seeds = [b"lp-lock-escrow", lp_lock.key().as_ref()],
+        bump
+    )]
+    pub lp_lock_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = contract.pool_state @ SafePumpError::InvalidPoolVault)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    // Only the bonded pool's LP can be locked, so a lock always backs this launch's liquidity
+    #[account(address = pool_state.load()?.lp_mint @ SafePumpError::InvalidLpMint)]
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct ExtendLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"lp-lock", lp_lock.contract.as_ref(), lp_lock.lp_mint.as_ref()],
+        bump = lp_lock.bump,
+        has_one = owner
+    )]
+    pub lp_lock: Account<'info, LpLock>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct WithdrawUnlockedLp<'info> {
+    #[account(
+        mut,
+        seeds = [b"lp-lock", lp_lock.contract.as_ref(), lp_lock.lp_mint.as_ref()],
+        bump = lp_lock.bump,
+        has_one = owner,
+        has_one = lp_mint,
+        constraint = lp_lock.escrow == lp_lock_escrow.key() @ SafePumpError::InvalidLpLockEscrow
+    )]
+    pub lp_lock: Account<'info, LpLock>,
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub lp_lock_escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub lp_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct InitializeBadgeRegistry<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + 8 + 8 + 1,
+        seeds = [b"badge-registry", mint.key().as_ref()],
+        bump
+    )]
+    pub badge_registry: Account<'info, BadgeRegistry>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+#[instruction(program_id: Pubkey)]
+pub struct RegisterMemeCoin<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        init_if_needed,
+        payer = deployer,
+        space = 8 + (64 * 1000) + 8 + 1,
+        seeds = [b"meme-coin-registry", contract.key().as_ref()],
+        bump
+    )]
+    pub meme_coin_registry: Account<'info, MemeCoinRegistry>,
+    #[account(
+        init,
+        payer = deployer,
+        space = 8 + 32 + 32 + 6 + 1,
+        seeds = [b"meme-coin-tax", contract.key().as_ref(), program_id.as_ref()],
+        bump
+    )]
+    pub meme_coin_tax: Account<'info, MemeCoinTax>,
+    #[account(mut)]
+    pub deployer: Signer<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts, Bumps)]
+#[instruction(program_id: Pubkey)]
+pub struct LowerMemeCoinTax<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"meme-coin-registry", contract.key().as_ref()],
+        bump = meme_coin_registry.bump
+    )]
+    pub meme_coin_registry: Account<'info, MemeCoinRegistry>,
+    #[account(
+        mut,
+        seeds = [b"meme-coin-tax", contract.key().as_ref(), program_id.as_ref()],
+        bump = meme_coin_tax.bump
+    )]
+    pub meme_coin_tax: Account<'info, MemeCoinTax>,
+    pub deployer: Signer<'info>,
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct GlobalTaxSwap<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, token::mint = wsol_mint, token::token_program = wsol_token_program)]
+    pub user_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>, // Pinned to contract.pool_vault for SafePump swaps in validate_swap
+    #[account(mut)]
+    pub badge_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub swap_rewards_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub lp_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"token-tax-vault", contract.key().as_ref()],
+        bump,
+        constraint = token_tax_vault.key() == contract.token_tax_vault @ SafePumpError::InvalidTokenTaxVault
+    )]
+    pub token_tax_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        token::mint = wsol_mint,
+        token::authority = contract,
+        token::token_program = wsol_token_program,
+        seeds = [b"reward-vault", contract.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = safepump_mint,
+        constraint = treasury_token_account.owner == contract.treasury_wallet @ SafePumpError::InvalidTreasuryAccount
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = contract.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 8 + 1 + 8 + 8 + 1,
+        seeds = [b"badge-progress", mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub badge_progress: Account<'info, BadgeProgress>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 8 + 16 + 8 + 8 + 1,
+        seeds = [b"user-rewards", mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    #[account(mut)]
+    pub user_safepump_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = contract.mint)]
+    pub safepump_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = USER_SWAP_DATA_SPACE,
+        seeds = [b"user-swap-data", user.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub user_swap_data: Account<'info, UserSwapData>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 8 + 8 + 8 + (40 * SNIPER_RING_SIZE) + 8,
+        seeds = [b"slot-buy-tracker", contract.key().as_ref()],
+        bump
+    )]
+    pub slot_buy_tracker: Account<'info, SlotBuyTracker>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 16 + 8 + 8 + 8 + 1,
+        seeds = [b"reward-accumulator", mint.key().as_ref()],
+        bump
+    )]
+    pub reward_distribution: Account<'info, RewardDistribution>,
+    #[account(
+        seeds = [b"meme-coin-registry", contract.key().as_ref()],
+        bump
+    )]
+    pub meme_coin_registry: Account<'info, MemeCoinRegistry>,
+    pub meme_coin_tax: Option<Account<'info, MemeCoinTax>>, // Required for meme coin swaps
+    #[account(mut)]
+    pub meme_coin_data: AccountLoader<'info, TokenContract>,
+    #[account(address = WSOL_MINT)]
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub wsol_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+    #[account(mut)]
+    pub pool_state: AccountInfo<'info>,
+    #[account(address = raydium_cp_swap::id())]
+    pub raydium_program: Program<'info, raydium_cp_swap::program::RaydiumCpSwap>,
+}
+
+impl<'info> GlobalTaxSwap<'info> {
+    // Shared gatekeeping for exact-in and exact-out swaps, returns whether this is a SafePump swap
+    // and the tax schedule that applies to it
+    fn validate_swap(&self, meme_program_id: Pubkey, deadline: Option<i64>, now: i64) -> Result<(bool, TaxSchedule)> {
+        let contract = &self.contract;
+        require!(contract.is_initialized, SafePumpError::NotInitialized);
+        if let Some(deadline) = deadline {
+            require!(now <= deadline, SafePumpError::DeadlineExceeded);
+        }
+
+        if contract.swap_count == 0 {
+            require!(
+                now - contract.bond_timestamp >= self.launch_config.anti_sniper_cooldown,
+                SafePumpError::AntiSniperCooldown
+            );
+        }
+
+        let is_safepump_swap = meme_program_id == self.safepump_mint.key();
+        let tax_schedule = swap_tax_schedule(
+            &contract.key(),
+            is_safepump_swap,
+            meme_program_id,
+            &self.meme_coin_registry,
+            self.meme_coin_tax.as_deref(),
+        )?;
+        // Pre-bond SafePump trades go through buy_on_curve/sell_on_curve instead
+        require!(
+            !is_safepump_swap || contract.launch_stage == LaunchStage::Finalized,
+            SafePumpError::InvalidLaunchStage
+        );
+        check_swap_pool(contract, is_safepump_swap, &self.pool_state.key(), &self.vault.key(), &self.sol_vault.key())?;
+        Ok((is_safepump_swap, tax_schedule))
+    }
+
+    // Update buy cap based on liquidity and market cap
+    fn update_buy_cap(&mut self) -> Result<()> {
+        let projection = BuyCapProjection::new(&self.contract, &self.launch_config, self.sol_vault.amount, self.vault.amount)?;
+        let contract = &mut self.contract;
+        if projection.liquidity_threshold_index != contract.liquidity_threshold_index
+            || projection.buy_cap_percentage != contract.buy_cap_percentage
+        {
+            msg!(
+                "Updated buy cap to {} bp at liquidity {} lamports, market cap {} lamports",
+                projection.buy_cap_percentage,
+                self.sol_vault.amount,
+                projection.market_cap
+            );
+        }
+        if contract.sell_lock_active && !projection.sell_lock_active {
+            msg!("Sell lock lifted: buy_cap={} bp, market_cap={} lamports", projection.buy_cap_percentage, projection.market_cap);
+        }
+        contract.liquidity_threshold_index = projection.liquidity_threshold_index;
+        contract.buy_cap_percentage = projection.buy_cap_percentage;
+        contract.sell_lock_active = projection.sell_lock_active;
+        Ok(())
+    }
+
+    // `balance` is the SafePump balance before the sale, which the allowance is measured against
+    fn enforce_sell_limits(&mut self, amount: u64, balance: u64, now: i64) -> Result<()> {
+        check_sell_limits(&self.contract, &self.launch_config, &mut self.user_swap_data, amount, balance, now)
+    }
+
+    // Holding cap for the current liquidity tier, checked against the balance after the buy
+    fn enforce_max_wallet(&self, balance_after: u64) -> Result<()> {
+        check_max_wallet(
+            &self.contract.key(),
+            &self.contract,
+            &self.launch_config,
+            &self.user_safepump_ata.key(),
+            &self.user_safepump_ata.owner,
+            balance_after,
+        )
+    }
+
+    // Per-slot buy limits for the first sniper_guard_slots slots after bonding
+    fn enforce_slot_limits(&mut self, buy_amount: u64, slot: u64) -> Result<()> {
+        let config = &self.launch_config;
+        if config.sniper_guard_slots == 0 || slot >= self.contract.bond_slot + config.sniper_guard_slots {
+            return Ok(());
+        }
+
+        let tracker = &mut self.slot_buy_tracker;
+        tracker.record_buy(self.user.key(), buy_amount, slot, config.max_buys_per_slot, config.max_buy_volume_per_slot)?;
+        msg!(
+            "Slot {} buy {} of {}: volume={} lamports",
+            slot,
+            tracker.buy_count,
+            config.max_buys_per_slot,
+            tracker.buy_volume
+        );
+        Ok(())
+    }
+
+    fn record_swap_rewards(&mut self, is_buy: bool, swapper_tax: u64, badge_tax: u64) -> Result<()> {
+        let user_key = self.user.key();
+        if is_buy {
+            let badge_progress = &mut self.badge_progress;
+            badge_progress.buy_swap_count = badge_progress.buy_swap_count
+                .checked_add(1)
+                .ok_or(SafePumpError::MathError)?;
+            msg!("Updated buy swap count for user {}: {}", user_key, badge_progress.buy_swap_count);
+        }
+
+        // Swapper rewards go straight to the swapper; badge tax only moves the accumulator
+        let user_rewards = &mut self.user_rewards;
+        user_rewards.pending_rewards = user_rewards.pending_rewards
+            .checked_add(swapper_tax)
+            .ok_or(SafePumpError::MathError)?;
+        self.user_swap_data.rewards_earned = self
+            .user_swap_data
+            .rewards_earned
+            .checked_add(swapper_tax)
+            .ok_or(SafePumpError::MathError)?;
+        self.reward_distribution.accrue_badge_rewards(badge_tax)?;
+        Ok(())
+    }
+
+    // (source, destination) balances for the side of the pool the user is trading
+    fn swap_balances(&self, sells_safepump: bool) -> (u64, u64) {
+        if sells_safepump {
+            (self.user_safepump_ata.amount, self.user_ata.amount)
+        } else {
+            (self.user_ata.amount, self.user_safepump_ata.amount)
+        }
+    }
+
+    fn swap_accounts(&self, remaining_accounts: &[AccountInfo<'info>], sells_safepump: bool) -> (AccountInfo<'info>, AccountInfo<'info>, Vec<AccountInfo<'info>>) {
+        let (source, destination) = if sells_safepump {
+            (self.user_safepump_ata.to_account_info(), self.user_ata.to_account_info())
+        } else {
+            (self.user_ata.to_account_info(), self.user_safepump_ata.to_account_info())
+        };
+        (source, destination, remaining_accounts.to_vec())
+    }
+
+    fn swap_base_in(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        sells_safepump: bool,
+        amount: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let (source, destination, remaining_accounts) = self.swap_accounts(remaining_accounts, sells_safepump);
+        let cpi_accounts = raydium_cp_swap::cpi::accounts::SwapBaseIn {
+            pool_state: self.pool_state.to_account_info(),
+            user_source_token: source,
+            user_destination_token: destination,
+            token_0_vault: self.vault.to_account_info(),
+            token_1_vault: self.sol_vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            remaining_accounts,
+        };
+        let cpi_program = self.raydium_program.to_account_info();
+        let instruction = SwapBaseInput {
+            amount,
+            minimum_amount_out,
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        raydium_cp_swap::cpi::swap_base_in(cpi_ctx, instruction)
+    }
+
+    fn swap_base_out(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        sells_safepump: bool,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        let (source, destination, remaining_accounts) = self.swap_accounts(remaining_accounts, sells_safepump);
+        let cpi_accounts = raydium_cp_swap::cpi::accounts::SwapBaseOut {
+            pool_state: self.pool_state.to_account_info(),
+            user_source_token: source,
+            user_destination_token: destination,
+            token_0_vault: self.vault.to_account_info(),
+            token_1_vault: self.sol_vault.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            remaining_accounts,
+        };
+        let cpi_program = self.raydium_program.to_account_info();
+        let instruction = SwapBaseOutput {
+            max_amount_in,
+            amount_out,
+        };
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        raydium_cp_swap::cpi::swap_base_out(cpi_ctx, instruction)
+    }
+
+    fn launch_tax_rate(&self, is_safepump_swap: bool, now: i64) -> u64 {
+        if is_safepump_swap {
+            self.launch_config.launch_tax_rate(self.contract.bond_timestamp, now)
+        } else {
+            0
+        }
+    }
+
+    // Route the launch tax, always denominated in SafePump tokens, to the configured destination
+    fn collect_launch_tax(&mut self, hook_accounts: &[AccountInfo<'info>], launch_tax: u64) -> Result<()> {
+        if launch_tax == 0 {
+            return Ok(());
+        }
+        match self.launch_config.launch_tax_destination {
+            LaunchTaxDestination::Burn => {
+                token_interface::burn(
+                    CpiContext::new(
+                        self.token_program.to_account_info(),
+                        Burn {
+                            mint: self.safepump_mint.to_account_info(),
+                            from: self.user_safepump_ata.to_account_info(),
+                            authority: self.user.to_account_info(),
+                        },
+                    ),
+                    launch_tax,
+                )?;
+                self.contract.burned_tokens = self.contract.burned_tokens.checked_add(launch_tax).ok_or(SafePumpError::MathError)?;
+                msg!("Burned {} tokens of launch tax", launch_tax);
+            }
+            LaunchTaxDestination::Lp | LaunchTaxDestination::Treasury => {
+                let destination = if self.launch_config.launch_tax_destination == LaunchTaxDestination::Lp {
+                    self.vault.to_account_info()
+                } else {
+                    self.treasury_token_account
+                        .as_ref()
+                        .ok_or(SafePumpError::MissingLaunchAccount)?
+                        .to_account_info()
+                };
+                transfer_safepump(
+                    CpiContext::new(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: self.user_safepump_ata.to_account_info(),
+                            mint: self.safepump_mint.to_account_info(),
+                            to: destination,
+                            authority: self.user.to_account_info(),
+                        },
+                    )
+                    .with_remaining_accounts(hook_accounts.to_vec()),
+                    launch_tax,
+                    self.safepump_mint.decimals,
+                )?;
+                msg!("Transferred {} tokens of launch tax to {:?}", launch_tax, self.launch_config.launch_tax_destination);
+            }
+        }
+        Ok(())
+    }
+
+    // Tax is taken in kind from the asset the user is spending
+    fn collect_tax(&mut self, hook_accounts: &[AccountInfo<'info>], sells_safepump: bool, total_tax: u64, lp_tax: u64) -> Result<()> {
+        if sells_safepump {
+            // LP and badge shares are held in tokens until convert_token_tax swaps them to SOL
+            transfer_safepump(
+                CpiContext::new(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.user_safepump_ata.to_account_info(),
+                        mint: self.safepump_mint.to_account_info(),
+                        to: self.token_tax_vault.to_account_info(),
+                        authority: self.user.to_account_info(),
+                    },
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                total_tax,
+                self.safepump_mint.decimals,
+            )?;
+            self.contract.token_tax_balance = self
+                .contract
+                .token_tax_balance
+                .checked_add(total_tax)
+                .ok_or(SafePumpError::MathError)?;
+            msg!("Transferred {} tokens (tax) to SafePump token tax vault", total_tax);
+            return Ok(());
+        }
+
+        // Swapper and badge shares back claim_rewards, so they sit in a vault the contract controls
+        let reward_tax = total_tax.checked_sub(lp_tax).ok_or(SafePumpError::MathError)?;
+        let pool_tax = total_tax - reward_tax;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.wsol_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.user_ata.to_account_info(),
+                    mint: self.wsol_mint.to_account_info(),
+                    to: self.reward_vault.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            reward_tax,
+            self.wsol_mint.decimals,
+        )?;
+        msg!("Transferred {} lamports (tax) to SafePump reward vault", reward_tax);
+        token_interface::transfer_checked(
+            CpiContext::new(
+                self.wsol_token_program.to_account_info(),
+                TransferChecked {
+                    from: self.user_ata.to_account_info(),
+                    mint: self.wsol_mint.to_account_info(),
+                    to: self.sol_vault.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            pool_tax,
+            self.wsol_mint.decimals,
+        )?;
+        self.contract.vault_sol_balance = self
+            .contract
+            .vault_sol_balance
+            .checked_add(pool_tax)
+            .ok_or(SafePumpError::MathError)?;
+        msg!("Transferred {} lamports (tax) to SafePump sol_vault", pool_tax);
+
+        if lp_tax > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    self.wsol_token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.user_ata.to_account_info(),
+                        mint: self.wsol_mint.to_account_info(),
+                        to: self.lp_vault.to_account_info(),
+                        authority: self.user.to_account_info(),
+                    },
+                ),
+                lp_tax,
+                self.wsol_mint.decimals,
+            )?;
+            msg!("Transferred {} lamports to SafePump LP vault", lp_tax);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Accounts, Bumps)]
+pub struct QuoteSwap<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    pub owner: AccountInfo<'info>,
+    pub user: AccountInfo<'info>,
+    #[account(token::mint = contract.mint, token::authority = user)]
+    pub user_safepump_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [b"user-swap-data", user.key().as_ref(), contract.mint.as_ref()],
+        bump
+    )]
+    pub user_swap_data: Option<Account<'info, UserSwapData>>,
+    #[account(
+        seeds = [b"meme-coin-registry", contract.key().as_ref()],
+        bump
+    )]
+    pub meme_coin_registry: Account<'info, MemeCoinRegistry>,
+    pub meme_coin_tax: Option<Account<'info, MemeCoinTax>>, // Required for meme coin quotes
+    pub pool_state: AccountLoader<'info, PoolState>,
+    pub vault: InterfaceAccount<'info, TokenAccount>, // Checked against the pool by check_swap_pool
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = pool_state.load()?.amm_config @ SafePumpError::InvalidAmmConfig)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct ConvertTokenTax<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump,
+        has_one = mint
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"token-tax-vault", contract.key().as_ref()],
+        bump,
+        constraint = token_tax_vault.key() == contract.token_tax_vault @ SafePumpError::InvalidTokenTaxVault
+    )]
+    pub token_tax_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        token::mint = wsol_mint,
+        token::authority = contract,
+        token::token_program = wsol_token_program,
+        seeds = [b"tax-sol-vault", contract.key().as_ref()],
+        bump
+    )]
+    pub tax_sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        token::mint = wsol_mint,
+        token::authority = contract,
+        token::token_program = wsol_token_program,
+        seeds = [b"reward-vault", contract.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + 16 + 8 + 8 + 8 + 1,
+        seeds = [b"reward-accumulator", mint.key().as_ref()],
+        bump
+    )]
+    pub reward_distribution: Account<'info, RewardDistribution>,
+    #[account(mut, address = contract.pool_vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = contract.pool_sol_vault)]
+    pub sol_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = contract.pool_state)]
+    pub pool_state: AccountInfo<'info>,
+    #[account(address = WSOL_MINT)]
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub wsol_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    #[account(address = raydium_cp_swap::id())]
+    pub raydium_program: Program<'info, raydium_cp_swap::program::RaydiumCpSwap>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct InitializeUserSwapData<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = USER_SWAP_DATA_SPACE,
+        seeds = [b"user-swap-data", user.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub user_swap_data: Account<'info, UserSwapData>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct MigrateUserSwapData<'info> {
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"user-swap-data", user.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub user_swap_data: AccountInfo<'info>,
+    pub user: AccountInfo<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct RecordHookSell<'info> {
+    #[account(seeds = [b"hook-authority"], bump, seeds::program = TRANSFER_HOOK_PROGRAM_ID)]
+    pub hook_authority: Signer<'info>,
+    pub contract: Account<'info, TokenContract>,
+    #[account(constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig)]
+    pub launch_config: Account<'info, LaunchConfig>,
+    pub seller: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"user-swap-data", seller.key().as_ref(), contract.mint.as_ref()],
+        bump
+    )]
+    pub user_swap_data: Account<'info, UserSwapData>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
+    pub user: Signer<'info>,
+    #[account(address = contract.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"reward-accumulator", mint.key().as_ref()],
+        bump = reward_distribution.bump
+    )]
+    pub reward_distribution: Account<'info, RewardDistribution>,
+    #[account(
+        mut,
+        seeds = [b"user-rewards", mint.key().as_ref(), user.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    #[account(
+        mut,
+        seeds = [b"reward-vault", contract.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = wsol_mint, token::authority = user, token::token_program = wsol_token_program)]
+    pub user_wsol_ata: InterfaceAccount<'info, TokenAccount>,
+    // Required while the user holds badge weight, the holder's badge ATA even if it was closed
+    pub badge_token_account: Option<AccountInfo<'info>>,
+    #[account(address = WSOL_MINT)]
+    pub wsol_mint: InterfaceAccount<'info, Mint>,
+    pub wsol_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts, Bumps)]
+#[instruction(page: u64)]
+pub struct AddBadgeHolder<'info> {
+    #[account(
+        mut,
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"badge-registry", mint.key().as_ref()],
+        bump = badge_registry.bump
+    )]
+    pub badge_registry: Account<'info, BadgeRegistry>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + 8 + (32 * BADGE_PAGE_SIZE) + BADGE_PAGE_SIZE + 8 + 1,
+        seeds = [b"badge-page", mint.key().as_ref(), &page.to_le_bytes()],
+        bump
+    )]
+    pub badge_page: Account<'info, BadgeHolderPage>,
+    #[account(
+        mut,
+        seeds = [b"badge-progress", mint.key().as_ref(), user.key().as_ref()],
+        bump = badge_progress.bump
+    )]
+    pub badge_progress: Account<'info, BadgeProgress>,
+    #[account(
         mut,
-        seeds = [b"contract", owner.key().as_ref()],
+        seeds = [b"reward-accumulator", mint.key().as_ref()],
+        bump = reward_distribution.bump
+    )]
+    pub reward_distribution: Account<'info, RewardDistribution>,
+    #[account(
+        mut,
+        seeds = [b"user-rewards", mint.key().as_ref(), user.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    #[account(mut, address = contract.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"user-swap-data", user.key().as_ref(), mint.key().as_ref()],
+        bump = user_swap_data.bump
+    )]
+    pub user_swap_data: Account<'info, UserSwapData>,
+    #[account(
+        mut,
+        seeds = [b"badge-mint", mint.key().as_ref(), user.key().as_ref()],
         bump
     )]
+    pub badge_mint: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&user.key(), &badge_mint.key(), &token_2022_program.key())
+    )]
+    pub badge_token_account: AccountInfo<'info>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AddBadgeHolder<'info> {
+    // Creates a non-transferable badge mint on first award and mints its one token to the holder.
+    // A revoked wallet that requalifies reuses its emptied mint, relabelled for the new tier
+    fn mint_badge(&self, badge_mint_bump: u8, tier: BadgeTier, uri: String) -> Result<()> {
+        let owner_key = self.owner.key();
+        let contract_seeds: &[&[u8]] = &[b"contract", owner_key.as_ref(), &[self.contract.bump]];
+        let token_program = self.token_2022_program.to_account_info();
+        let badge_mint = self.badge_mint.to_account_info();
+        let contract = self.contract.to_account_info();
+
+        if badge_mint.data_is_empty() {
+            self.create_badge_mint(badge_mint_bump, tier, uri)?;
+        } else {
+            let supply = InterfaceAccount::<Mint>::try_from(&badge_mint)?.supply;
+            require!(supply == 0, SafePumpError::AlreadyBadgeHolder);
+            update_badge_metadata(
+                &token_program,
+                &self.system_program.to_account_info(),
+                &self.user.to_account_info(),
+                &badge_mint,
+                &contract,
+                contract_seeds,
+                vec![(Field::Name, tier.badge_name().to_string()), (Field::Uri, uri)],
+            )?;
+        }
+
+        // The holder may have closed the empty ATA after a revocation
+        associated_token::create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: self.user.to_account_info(),
+                associated_token: self.badge_token_account.to_account_info(),
+                authority: self.user.to_account_info(),
+                mint: badge_mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+        // Supply stays at one: the contract only mints into a badge mint whose supply is zero
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                token_program,
+                MintTo {
+                    mint: badge_mint,
+                    to: self.badge_token_account.to_account_info(),
+                    authority: contract,
+                },
+                &[contract_seeds],
+            ),
+            1,
+        )
+    }
+
+    fn create_badge_mint(&self, badge_mint_bump: u8, tier: BadgeTier, uri: String) -> Result<()> {
+        let mint_key = self.mint.key();
+        let user_key = self.user.key();
+        let owner_key = self.owner.key();
+        let badge_mint_seeds: &[&[u8]] = &[b"badge-mint", mint_key.as_ref(), user_key.as_ref(), &[badge_mint_bump]];
+        let contract_seeds: &[&[u8]] = &[b"contract", owner_key.as_ref(), &[self.contract.bump]];
+        let token_program = self.token_2022_program.to_account_info();
+        let badge_mint = self.badge_mint.to_account_info();
+        let contract = self.contract.to_account_info();
+
+        // Size the account for its extensions and fund the metadata TLV entry up front
+        let mint_space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::PermanentDelegate,
+            ExtensionType::MetadataPointer,
+        ])?;
+        let token_metadata = TokenMetadata {
+            name: tier.badge_name().to_string(),
+            symbol: BADGE_SYMBOL.to_string(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        let lamports = Rent::get()?.minimum_balance(mint_space + token_metadata.tlv_size_of()?);
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: self.user.to_account_info(),
+                    to: badge_mint.clone(),
+                },
+                &[badge_mint_seeds],
+            ),
+            lamports,
+            mint_space as u64,
+            token_program.key,
+        )?;
+
+        non_transferable_mint_initialize(CpiContext::new(
+            token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: token_program.clone(),
+                mint: badge_mint.clone(),
+            },
+        ))?;
+        permanent_delegate_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                PermanentDelegateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: badge_mint.clone(),
+                },
+            ),
+            &self.contract.key(),
+        )?;
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: badge_mint.clone(),
+                },
+            ),
+            Some(self.contract.key()),
+            Some(badge_mint.key()),
+        )?;
+        token_2022::initialize_mint2(
+            CpiContext::new(token_program.clone(), InitializeMint2 { mint: badge_mint.clone() }),
+            0,
+            &self.contract.key(),
+            None,
+        )?;
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: token_program,
+                    mint: badge_mint.clone(),
+                    metadata: badge_mint,
+                    mint_authority: contract.clone(),
+                    update_authority: contract,
+                },
+                &[contract_seeds],
+            ),
+            tier.badge_name().to_string(),
+            BADGE_SYMBOL.to_string(),
+            uri,
+        )
+    }
+}
+
+#[derive(Accounts, Bumps)]
+pub struct UpgradeBadge<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
     pub contract: Account<'info, TokenContract>,
+    pub owner: AccountInfo<'info>,
     #[account(mut)]
     pub user: Signer<'info>,
-    #[account(mut)]
-    pub user_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub badge_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub swap_rewards_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub sol_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"badge-holders", mint.key().as_ref()],
-        bump
+        seeds = [b"badge-page", mint.key().as_ref(), &badge_progress.page.to_le_bytes()],
+        bump = badge_page.bump
     )]
-    pub badge_holders: Account<'info, BadgeHolders>,
-    #[account(mut)]
-    pub user_safepump_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub safepump_mint: Account<'info, Mint>,
+    pub badge_page: Account<'info, BadgeHolderPage>,
+    #[account(
+        seeds = [b"badge-progress", mint.key().as_ref(), user.key().as_ref()],
+        bump = badge_progress.bump,
+        constraint = badge_progress.is_holder @ SafePumpError::NotBadgeHolder
+    )]
+    pub badge_progress: Account<'info, BadgeProgress>,
+    #[account(
+        mut,
+        seeds = [b"reward-accumulator", mint.key().as_ref()],
+        bump = reward_distribution.bump
+    )]
+    pub reward_distribution: Account<'info, RewardDistribution>,
+    #[account(
+        mut,
+        seeds = [b"user-rewards", mint.key().as_ref(), user.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    #[account(address = contract.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + 8 + 1,
         seeds = [b"user-swap-data", user.key().as_ref(), mint.key().as_ref()],
-        bump
+        bump = user_swap_data.bump
     )]
     pub user_swap_data: Account<'info, UserSwapData>,
     #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + (40 * MAX_BADGE_HOLDERS) + 8 + 8 + 1,
-        seeds = [b"reward-distribution", mint.key().as_ref()],
+        mut,
+        seeds = [b"badge-mint", mint.key().as_ref(), user.key().as_ref()],
         bump
     )]
+    pub badge_mint: InterfaceAccount<'info, Mint>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts, Bumps)]
+pub struct RevokeBadge<'info> {
+    #[account(
+        seeds = [b"contract", owner.key().as_ref()],
+        bump = contract.bump
+    )]
+    pub contract: Account<'info, TokenContract>,
+    #[account(
+        seeds = [b"launch-config", owner.key().as_ref()],
+        bump = launch_config.bump,
+        constraint = launch_config.key() == contract.launch_config @ SafePumpError::InvalidLaunchConfig
+    )]
+    pub launch_config: Account<'info, LaunchConfig>,
+    pub owner: AccountInfo<'info>,
+    pub holder: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"badge-registry", mint.key().as_ref()],
+        bump = badge_registry.bump
+    )]
+    pub badge_registry: Account<'info, BadgeRegistry>,
+    #[account(
+        mut,
+        seeds = [b"badge-page", mint.key().as_ref(), &badge_progress.page.to_le_bytes()],
+        bump = badge_page.bump
+    )]
+    pub badge_page: Account<'info, BadgeHolderPage>,
+    #[account(
+        mut,
+        seeds = [b"badge-progress", mint.key().as_ref(), holder.key().as_ref()],
+        bump = badge_progress.bump,
+        constraint = badge_progress.is_holder @ SafePumpError::NotBadgeHolder
+    )]
+    pub badge_progress: Account<'info, BadgeProgress>,
+    #[account(
+        mut,
+        seeds = [b"reward-accumulator", mint.key().as_ref()],
+        bump = reward_distribution.bump
+    )]
     pub reward_distribution: Account<'info, RewardDistribution>,
     #[account(
-        seeds = [b"meme-coin-registry", contract.key().as_ref()],
+        mut,
+        seeds = [b"user-rewards", mint.key().as_ref(), holder.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    #[account(address = contract.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"user-swap-data", holder.key().as_ref(), mint.key().as_ref()],
+        bump = user_swap_data.bump
+    )]
+    pub user_swap_data: Account<'info, UserSwapData>,
+    // Passed even when closed, so an emptied wallet still counts as a zero balance
+    #[account(address = get_associated_token_address_with_program_id(&holder.key(), &mint.key(), &token_2022_program.key()))]
+    pub holder_safepump_ata: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"badge-mint", mint.key().as_ref(), holder.key().as_ref()],
         bump
     )]
-    pub meme_coin_registry: Account<'info, MemeCoinRegistry>,
-    #[account(mut)]
-    pub meme_coin_data: AccountLoader<'info, TokenContract>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
-    #[account(mut)]
-    pub pool_state: AccountInfo<'info>,
-    #[account(address = raydium_cp_swap::id())]
-    pub raydium_program: Program<'info, raydium_cp_swap::program::RaydiumCpSwap>,
+    pub badge_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&holder.key(), &badge_mint.key(), &token_2022_program.key())
+    )]
+    pub badge_token_account: AccountInfo<'info>,
+    pub token_2022_program: Program<'info, Token2022>,
+}
+
+#[account]
+#[derive(Copy, Clone)]
+#[zero_copy]
+pub struct TokenContract {
+    pub is_initialized: bool,
+    pub total_supply: u64,
+    pub treasury_wallet: Pubkey,
+    pub swap_count: u64,
+    pub total_swapped: u64,
+    pub vault_sol_balance: u64,
+    pub vault_token_balance: u64,
+    pub burned_tokens: u64,
+    pub burn_percentage: u8,
+    pub bond_timestamp: i64,
+    pub buy_cap_percentage: u64, // Current buy cap in basis points (e.g., 10 = 0.1%)
+    pub sell_lock_active: bool, // True until buy cap reaches 0.25% (25 bp)
+    pub liquidity_threshold_index: u8, // Tracks current step (0 to 4)
+    pub friends_wallets: [Pubkey; MAX_FRIENDS_WALLETS], // Fixed-size array for friends
+    pub friends_amounts: [u64; MAX_FRIENDS_WALLETS], // Fixed-size array for amounts
+    pub deployer_amount: u64, // Deployer allocation
+    pub launch_config: Pubkey, // LaunchConfig PDA this contract was launched with
+    pub vesting_escrow: Pubkey, // Token account holding unclaimed deployer and friends allocations
+    pub vested_tokens: u64, // Tokens still held in the vesting escrow
+    pub mint: Pubkey,
+    pub launch_stage: LaunchStage, // Progress through the staged launch instructions
+    pub metadata_frozen: bool, // Metadata update authority permanently cleared
+    pub mint_renounced: bool, // Mint authority set to None by finalize_launch
+    pub freeze_renounced: bool, // Freeze authority set to None by finalize_launch
+    pub presale_active: bool, // Presale opened and not yet finalized, blocks bonding
+    pub token_tax_vault: Pubkey, // Token account holding sell-side tax collected in SafePump tokens
+    pub token_tax_balance: u64, // Token tax awaiting conversion by convert_token_tax
+    pub token_tax_converted: u64, // Lifetime token tax swapped to SOL
+    pub bond_slot: u64, // Slot the Raydium pool was created in, starts the sniper guard window
+    pub pool_vault: Pubkey, // Raydium SafePump vault, transfers out of it are buys for the transfer hook
+    pub pool_sol_vault: Pubkey, // Raydium WSOL vault of the same pool
+    pub pool_state: Pubkey, // Raydium pool the launch bonded into, the only pool convert_token_tax may swap through
+    pub bump: u8,
+}
+
+impl TokenContract {
+    pub const SPACE: usize = 8 // Discriminator
+        + 1 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + 1 // is_initialized through liquidity_threshold_index
+        + (32 * MAX_FRIENDS_WALLETS) + (8 * MAX_FRIENDS_WALLETS) + 8 // Friends and deployer allocations
+        + 32 + 32 + 8 + 32 // launch_config, vesting_escrow, vested_tokens, mint
+        + 1 + 1 + 1 + 1 + 1 // launch_stage and launch flags
+        + 32 + 8 + 8 // Token tax vault and balances
+        + 8 + 32 // bond_slot, pool_vault
+        + 32 + 32 // pool_sol_vault, pool_state
+        + 1; // bump
+
+    pub fn require_stage(&self, stage: LaunchStage) -> Result<()> {
+        require!(self.launch_stage == stage, SafePumpError::InvalidLaunchStage);
+        Ok(())
+    }
+
+    // Protocol-held accounts sit outside the max-wallet cap: anything the contract owns, plus the pool vaults
+    pub fn is_max_wallet_exempt(&self, contract_key: &Pubkey, token_account: &Pubkey, holder: &Pubkey) -> bool {
+        holder == contract_key || *token_account == self.pool_vault || *token_account == self.pool_sol_vault
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LaunchStage {
+    Created, // Parameters recorded by initialize_contract
+    Allocated, // Vesting escrow and pool vault minted by mint_allocations
+    VaultsFunded, // Reward vaults and pool SOL funded by fund_vaults
+    Bonded, // Raydium pool created by bond_to_raydium or migrate_to_raydium
+    Finalized, // Launch complete, public trading open
+}
+
+#[account]
+pub struct LaunchConfig {
+    pub authority: Pubkey,
+    pub anti_sniper_cooldown: i64, // Seconds after bonding before the first swap
+    pub max_sell_percent: u64, // Basis points of holdings per sell cooldown
+    pub sell_cooldown: i64, // Seconds between sells
+    pub pool_sol_amount: u64, // Lamports seeded into the Raydium pool
+    pub initial_vault_amount: u64, // Tokens minted to each reward vault
+    pub max_allocation_percent: u64, // Basis points for deployer + friends
+    pub liquidity_thresholds: [u64; LIQUIDITY_TIERS], // Lamports per liquidity step
+    pub buy_cap_percentages: [u64; LIQUIDITY_TIERS], // Buy cap in basis points per step
+    pub market_cap_threshold_start: u64, // Lamports where market cap scaling begins
+    pub market_cap_threshold_end: u64, // Lamports where market cap scaling ends
+    pub buy_cap_start: u64, // Buy cap in basis points at market_cap_threshold_start
+    pub buy_cap_end: u64, // Buy cap in basis points at market_cap_threshold_end
+    pub sell_unlock_buy_cap: u64, // Buy cap in basis points that lifts the sell lock
+    pub vesting_cliff: i64, // Seconds after bonding before allocations start unlocking
+    pub vesting_duration: i64, // Seconds after bonding until allocations are fully unlocked
+    pub curve_sol_target: u64, // Lamports raised on the bonding curve before migration, 0 bonds immediately
+    pub curve_virtual_sol_reserves: u64, // Virtual lamports seeding the curve price
+    pub curve_supply_percent: u64, // Basis points of vault tokens sold on the curve
+    pub launch_tax_start: u64, // Extra basis points charged on SafePump swaps at bond time, 0 disables
+    pub launch_tax_duration: i64, // Seconds after bonding until the launch tax reaches zero
+    pub launch_tax_half_life: i64, // Seconds per halving when the decay is exponential
+    pub launch_tax_decay: LaunchTaxDecay,
+    pub launch_tax_destination: LaunchTaxDestination,
+    pub sniper_guard_slots: u64, // Slots after bonding with per-slot buy limits, 0 disables
+    pub max_buys_per_slot: u64, // Buys accepted per slot during the sniper guard window
+    pub max_buy_volume_per_slot: u64, // Lamports of buys accepted per slot during the sniper guard window
+    pub max_wallet_percentages: [u64; LIQUIDITY_TIERS], // Max holding in basis points of supply per step
+    pub badge_max_sell_percent: u64, // Basis points of holdings a badge holder may sell after the award
+    pub badge_inactivity_period: i64, // Seconds without trading before a badge can be revoked, 0 disables
+    pub badge_min_balance: u64, // SafePump tokens a badge holder must keep, 0 disables
+    pub bump: u8,
+}
+
+impl LaunchConfig {
+    // First maintenance rule a badge holder breaks, if any
+    pub fn badge_revocation(
+        &self,
+        user_swap_data: &UserSwapData,
+        sell_volume_at_award: u64,
+        balance: u64,
+        now: i64,
+    ) -> Result<Option<BadgeRevocation>> {
+        // Sold fraction is measured against holdings before those sells. sell_volume is the gross
+        // debit including token and launch tax, the same amount the sell allowance is charged
+        let sold = user_swap_data.sell_volume.saturating_sub(sell_volume_at_award);
+        let holdings = balance.checked_add(sold).ok_or(SafePumpError::MathError)?;
+        if sold > 0 && (sold as u128) * (MAX_CAP_BPS as u128) > (holdings as u128) * (self.badge_max_sell_percent as u128) {
+            return Ok(Some(BadgeRevocation::Sold));
+        }
+        let last_trade = user_swap_data
+            .first_trade_timestamp
+            .max(user_swap_data.last_buy_timestamp)
+            .max(user_swap_data.last_sell_timestamp);
+        if self.badge_inactivity_period > 0 && now.saturating_sub(last_trade) >= self.badge_inactivity_period {
+            return Ok(Some(BadgeRevocation::Inactive));
+        }
+        if balance < self.badge_min_balance {
+            return Ok(Some(BadgeRevocation::LowBalance));
+        }
+        Ok(None)
+    }
+
+    // Extra basis points charged on top of the swap tax at `now`
+    pub fn launch_tax_rate(&self, bond_timestamp: i64, now: i64) -> u64 {
+        if self.launch_tax_start == 0 || bond_timestamp == 0 {
+            return 0;
+        }
+        let elapsed = (now - bond_timestamp).max(0);
+        if elapsed >= self.launch_tax_duration {
+            return 0;
+        }
+        match self.launch_tax_decay {
+            LaunchTaxDecay::Linear => {
+                (self.launch_tax_start as u128 * (self.launch_tax_duration - elapsed) as u128
+                    / self.launch_tax_duration as u128) as u64
+            }
+            LaunchTaxDecay::Exponential => {
+                // Halve every half-life, interpolating linearly within each period
+                let halvings = elapsed / self.launch_tax_half_life;
+                if halvings >= 64 {
+                    return 0;
+                }
+                let rate = self.launch_tax_start >> halvings;
+                let progress = elapsed % self.launch_tax_half_life;
+                rate - (rate / 2) * progress as u64 / self.launch_tax_half_life as u64
+            }
+        }
+    }
+
+    pub fn max_wallet_amount(&self, total_supply: u64, liquidity_threshold_index: u8) -> Result<u64> {
+        let tier = (liquidity_threshold_index as usize).min(LIQUIDITY_TIERS - 1);
+        basis_points(total_supply, self.max_wallet_percentages[tier])
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LaunchTaxDecay {
+    Linear, // Falls to zero at launch_tax_duration
+    Exponential, // Halves every launch_tax_half_life, cut off at launch_tax_duration
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LaunchTaxDestination {
+    Burn, // Burned from the swapper's SafePump tokens
+    Lp, // Added to the pool's SafePump vault
+    Treasury, // Sent to the treasury wallet's SafePump token account
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LaunchConfigParams {
+    pub anti_sniper_cooldown: i64,
+    pub max_sell_percent: u64,
+    pub sell_cooldown: i64,
+    pub pool_sol_amount: u64,
+    pub initial_vault_amount: u64,
+    pub max_allocation_percent: u64,
+    pub liquidity_thresholds: [u64; LIQUIDITY_TIERS],
+    pub buy_cap_percentages: [u64; LIQUIDITY_TIERS],
+    pub market_cap_threshold_start: u64,
+    pub market_cap_threshold_end: u64,
+    pub buy_cap_start: u64,
+    pub buy_cap_end: u64,
+    pub sell_unlock_buy_cap: u64,
+    pub vesting_cliff: i64,
+    pub vesting_duration: i64,
+    pub curve_sol_target: u64,
+    pub curve_virtual_sol_reserves: u64,
+    pub curve_supply_percent: u64,
+    pub launch_tax_start: u64,
+    pub launch_tax_duration: i64,
+    pub launch_tax_half_life: i64,
+    pub launch_tax_decay: LaunchTaxDecay,
+    pub launch_tax_destination: LaunchTaxDestination,
+    pub sniper_guard_slots: u64,
+    pub max_buys_per_slot: u64,
+    pub max_buy_volume_per_slot: u64,
+    pub max_wallet_percentages: [u64; LIQUIDITY_TIERS],
+    pub badge_max_sell_percent: u64,
+    pub badge_inactivity_period: i64,
+    pub badge_min_balance: u64,
+}
+
+impl LaunchConfigParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (0..=MAX_ANTI_SNIPER_COOLDOWN).contains(&self.anti_sniper_cooldown),
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            self.max_sell_percent > 0 && self.max_sell_percent <= MAX_CAP_BPS,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            (0..=MAX_SELL_COOLDOWN).contains(&self.sell_cooldown),
+            SafePumpError::InvalidLaunchConfig
+        );
+        // Curve launches raise their pool SOL instead of the deployer fronting it
+        require!(
+            self.curve_sol_target > 0 || self.pool_sol_amount >= MIN_POOL_SOL_AMOUNT,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(self.initial_vault_amount <= MAX_SUPPLY, SafePumpError::InvalidLaunchConfig);
+        require!(
+            self.max_allocation_percent <= MAX_ALLOCATION_PERCENT,
+            SafePumpError::InvalidLaunchConfig
+        );
+
+        // Liquidity steps must strictly increase, buy caps and wallet caps must never shrink
+        for i in 1..LIQUIDITY_TIERS {
+            require!(
+                self.max_wallet_percentages[i] >= self.max_wallet_percentages[i - 1],
+                SafePumpError::InvalidLaunchConfig
+            );
+            require!(
+                self.liquidity_thresholds[i] > self.liquidity_thresholds[i - 1],
+                SafePumpError::InvalidLaunchConfig
+            );
+            require!(
+                self.buy_cap_percentages[i] >= self.buy_cap_percentages[i - 1],
+                SafePumpError::InvalidLaunchConfig
+            );
+        }
+        require!(self.buy_cap_percentages[0] > 0, SafePumpError::InvalidLaunchConfig);
+        // A wallet must be able to hold at least one max-size buy
+        require!(
+            self.max_wallet_percentages[0] >= self.buy_cap_percentages[0]
+                && self.max_wallet_percentages[LIQUIDITY_TIERS - 1] <= MAX_CAP_BPS,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            self.buy_cap_percentages[LIQUIDITY_TIERS - 1] <= self.buy_cap_start,
+            SafePumpError::InvalidLaunchConfig
+        );
+
+        require!(
+            self.market_cap_threshold_start < self.market_cap_threshold_end,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            self.buy_cap_start <= self.buy_cap_end && self.buy_cap_end <= MAX_CAP_BPS,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            self.sell_unlock_buy_cap > 0 && self.sell_unlock_buy_cap <= self.buy_cap_end,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            self.vesting_duration > 0 && self.vesting_duration <= MAX_VESTING_DURATION,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            self.vesting_cliff >= 0 && self.vesting_cliff <= self.vesting_duration,
+            SafePumpError::InvalidLaunchConfig
+        );
+        if self.curve_sol_target > 0 {
+            require!(
+                self.curve_sol_target >= MIN_POOL_SOL_AMOUNT
+                    && self.curve_virtual_sol_reserves >= MIN_POOL_SOL_AMOUNT,
+                SafePumpError::InvalidLaunchConfig
+            );
+            require!(
+                self.curve_supply_percent > 0 && self.curve_supply_percent < MAX_CAP_BPS,
+                SafePumpError::InvalidLaunchConfig
+            );
+        }
+        require!(self.launch_tax_start <= MAX_LAUNCH_TAX, SafePumpError::InvalidLaunchConfig);
+        if self.launch_tax_start > 0 {
+            require!(
+                self.launch_tax_duration > 0 && self.launch_tax_duration <= MAX_LAUNCH_TAX_DURATION,
+                SafePumpError::InvalidLaunchConfig
+            );
+            if self.launch_tax_decay == LaunchTaxDecay::Exponential {
+                require!(
+                    self.launch_tax_half_life > 0 && self.launch_tax_half_life <= self.launch_tax_duration,
+                    SafePumpError::InvalidLaunchConfig
+                );
+            }
+        }
+        require!(self.sniper_guard_slots <= MAX_SNIPER_GUARD_SLOTS, SafePumpError::InvalidLaunchConfig);
+        if self.sniper_guard_slots > 0 {
+            require!(
+                self.max_buys_per_slot > 0 && self.max_buys_per_slot <= SNIPER_RING_SIZE as u64,
+                SafePumpError::InvalidLaunchConfig
+            );
+            require!(self.max_buy_volume_per_slot > 0, SafePumpError::InvalidLaunchConfig);
+        }
+        require!(
+            self.badge_max_sell_percent > 0 && self.badge_max_sell_percent <= MAX_CAP_BPS,
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(
+            (0..=MAX_BADGE_INACTIVITY_PERIOD).contains(&self.badge_inactivity_period),
+            SafePumpError::InvalidLaunchConfig
+        );
+        require!(self.badge_min_balance <= MAX_SUPPLY, SafePumpError::InvalidLaunchConfig);
+        Ok(())
+    }
+}
+
+// Buy cap state after applying the liquidity and market cap tiers to the current pool
+pub struct BuyCapProjection {
+    pub liquidity_threshold_index: u8,
+    pub buy_cap_percentage: u64,
+    pub sell_lock_active: bool,
+    pub market_cap: u64,
+}
+
+impl BuyCapProjection {
+    pub fn new(contract: &TokenContract, config: &LaunchConfig, pool_sol_amount: u64, pool_token_amount: u64) -> Result<Self> {
+        let mut liquidity_threshold_index = contract.liquidity_threshold_index;
+        let mut buy_cap_percentage = contract.buy_cap_percentage;
+        let current_liquidity = pool_sol_amount; // SOL balance in lamports
+
+        // Update buy cap based on liquidity thresholds
+        let current_index = liquidity_threshold_index as usize;
+        if current_index < LIQUIDITY_TIERS - 1 && current_liquidity >= config.liquidity_thresholds[current_index + 1] {
+            liquidity_threshold_index += 1;
+            buy_cap_percentage = config.buy_cap_percentages[liquidity_threshold_index as usize];
+        }
+
+        // Estimate market cap: (pool_sol_amount / pool_token_amount) * total_supply
+        let market_cap = if pool_token_amount > 0 {
+            (pool_sol_amount as u128)
+                .checked_mul(contract.total_supply as u128)
+                .ok_or(SafePumpError::MathError)?
+                .checked_div(pool_token_amount as u128)
+                .ok_or(SafePumpError::MathError)?
+                as u64
+        } else {
+            0
+        };
+
+        // Scale buy cap linearly between the configured market cap thresholds
+        if market_cap > config.market_cap_threshold_start {
+            let market_cap_range = config.market_cap_threshold_end - config.market_cap_threshold_start;
+            let buy_cap_range = config.buy_cap_end - config.buy_cap_start;
+            let market_cap_progress = market_cap.saturating_sub(config.market_cap_threshold_start);
+            let buy_cap_increase = (market_cap_progress as u128)
+                .checked_mul(buy_cap_range as u128)
+                .ok_or(SafePumpError::MathError)?
+                .checked_div(market_cap_range as u128)
+                .ok_or(SafePumpError::MathError)?
+                as u64;
+            buy_cap_percentage = (config.buy_cap_start + buy_cap_increase).min(config.buy_cap_end);
+        }
+
+        // Unlock sells when buy cap reaches the configured threshold
+        let sell_lock_active = contract.sell_lock_active && buy_cap_percentage < config.sell_unlock_buy_cap;
+
+        Ok(Self {
+            liquidity_threshold_index,
+            buy_cap_percentage,
+            sell_lock_active,
+            market_cap,
+        })
+    }
+
+    pub fn max_buy_amount(&self, total_supply: u64) -> u64 {
+        total_supply * self.buy_cap_percentage / 10_000
+    }
 }
 
-#[derive(Accounts, Bumps)]
-pub struct DistributeRewards<'info> {
-    #[account(
-        mut,
-        seeds = [b"contract", owner.key().as_ref()],
-        bump
-    )]
-    pub contract: Account<'info, TokenContract>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    #[account(mut)]
-    pub user: AccountInfo<'info>,
-    #[account(mut)]
-    pub badge_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub swap_rewards_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub sol_vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub safepump_mint: Account<'info, Mint>,
-    #[account(
-        mut,
-        seeds = [b"badge-holders", safepump_mint.key().as_ref()],
-        bump
-    )]
-    pub badge_holders: Account<'info, BadgeHolders>,
-    #[account(
-        mut,
-        seeds = [b"reward-distribution", safepump_mint.key().as_ref()],
-        bump
-    )]
-    pub reward_distribution: Account<'info, RewardDistribution>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    pub swap_amount: u64, // Portion of amount_in routed through the pool
+    pub expected_amount_out: u64,
+    pub total_cost: u64, // Debited from the wallet, sells add the token and launch tax to amount_in
+    pub total_tax: u64,
+    pub lp_tax: u64,
+    pub swapper_tax: u64,
+    pub badge_tax: u64,
+    pub launch_tax_rate: u64, // Current decaying launch tax in basis points
+    pub launch_tax: u64, // Launch tax in SafePump tokens
+    pub buy_cap_percentage: u64, // Effective buy cap in basis points
+    pub max_buy_amount: u64,
+    pub max_wallet_amount: u64, // Max SafePump holding for the projected liquidity tier
+    pub market_cap: u64,
+    pub sell_lock_active: bool,
+    pub max_sell_amount: u64, // Remaining sell allowance in the trailing sell_cooldown window
+    pub sell_window_resets_in: i64, // Seconds until the oldest sell in the window stops counting
+    pub anti_sniper_remaining: i64, // Seconds until the first swap is allowed
 }
 
-#[derive(Accounts, Bumps)]
-pub struct AddBadgeHolder<'info> {
-    #[account(
-        mut,
-        seeds = [b"contract", owner.key().as_ref()],
-        bump
-    )]
-    pub contract: Account<'info, TokenContract>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    #[account(
-        mut,
-        seeds = [b"badge-holders", mint.key().as_ref()],
-        bump
-    )]
-    pub badge_holders: Account<'info, BadgeHolders>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    pub system_program: Program<'info, System>,
+#[account]
+pub struct BondingCurve {
+    pub contract: Pubkey,
+    pub vault: Pubkey, // Token vault backing the curve and later the Raydium pool
+    pub sol_vault: Pubkey, // WSOL vault collecting the raise
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64, // Lamports actually raised
+    pub real_token_reserves: u64, // Tokens still for sale on the curve
+    pub sol_target: u64, // Raise that completes the curve
+    pub complete: bool, // Trading halted, ready for migrate_to_raydium
+    pub migrated: bool,
+    pub bump: u8,
+}
+
+impl BondingCurve {
+    // Constant product on virtual reserves, rounded in the curve's favour
+    pub fn tokens_out_for_sol(&self, sol_in: u64) -> Result<u64> {
+        let k = (self.virtual_sol_reserves as u128)
+            .checked_mul(self.virtual_token_reserves as u128)
+            .ok_or(SafePumpError::MathError)?;
+        let new_sol = (self.virtual_sol_reserves as u128)
+            .checked_add(sol_in as u128)
+            .ok_or(SafePumpError::MathError)?;
+        let new_tokens = k.checked_add(new_sol - 1).ok_or(SafePumpError::MathError)? / new_sol;
+        Ok((self.virtual_token_reserves as u128)
+            .checked_sub(new_tokens)
+            .ok_or(SafePumpError::MathError)? as u64)
+    }
+
+    pub fn sol_out_for_tokens(&self, tokens_in: u64) -> Result<u64> {
+        let k = (self.virtual_sol_reserves as u128)
+            .checked_mul(self.virtual_token_reserves as u128)
+            .ok_or(SafePumpError::MathError)?;
+        let new_tokens = (self.virtual_token_reserves as u128)
+            .checked_add(tokens_in as u128)
+            .ok_or(SafePumpError::MathError)?;
+        let new_sol = k.checked_add(new_tokens - 1).ok_or(SafePumpError::MathError)? / new_tokens;
+        Ok((self.virtual_sol_reserves as u128)
+            .checked_sub(new_sol)
+            .ok_or(SafePumpError::MathError)? as u64)
+    }
+
+    // Tokens pairing with `sol` at the curve's closing price, rounded down so the pool never opens cheaper
+    pub fn pool_tokens_for_sol(&self, sol: u64) -> Result<u64> {
+        Ok((sol as u128)
+            .checked_mul(self.virtual_token_reserves as u128)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(self.virtual_sol_reserves as u128)
+            .ok_or(SafePumpError::MathError)? as u64)
+    }
 }
 
 #[account]
-#[derive(Copy, Clone)]
-#[zero_copy]
-pub struct TokenContract {
-    pub is_initialized: bool,
-    pub total_supply: u64,
-    pub treasury_wallet: Pubkey,
-    pub swap_count: u64,
-    pub total_swapped: u64,
-    pub vault_sol_balance: u64,
-    pub vault_token_balance: u64,
-    pub burned_tokens: u64,
-    pub burn_percentage: u8,
-    pub bond_timestamp: i64,
-    pub buy_cap_percentage: u64, // Current buy cap in basis points (e.g., 10 = 0.1%)
-    pub sell_lock_active: bool, // True until buy cap reaches 0.25% (25 bp)
-    pub liquidity_threshold_index: u8, // Tracks current step (0 to 4)
-    pub friends_wallets: [Pubkey; MAX_FRIENDS_WALLETS], // Fixed-size array for friends
-    pub friends_amounts: [u64; MAX_FRIENDS_WALLETS], // Fixed-size array for amounts
-    pub deployer_amount: u64, // Deployer allocation
+pub struct VestingSchedule {
+    pub contract: Pubkey,
+    pub beneficiary: Pubkey,
+    pub total_amount: u64, // Tokens allocated after burn
+    pub claimed_amount: u64,
+    pub start_timestamp: i64,
+    pub cliff_timestamp: i64, // Nothing unlocks before this
+    pub end_timestamp: i64, // Everything is unlocked from this point
+    pub bump: u8,
+}
+
+impl VestingSchedule {
+    // Linear unlock from start_timestamp to end_timestamp, gated by the cliff
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        if now < self.cliff_timestamp {
+            return Ok(0);
+        }
+        if now >= self.end_timestamp {
+            return Ok(self.total_amount);
+        }
+        let elapsed = (now - self.start_timestamp) as u128;
+        let duration = (self.end_timestamp - self.start_timestamp) as u128;
+        Ok((self.total_amount as u128)
+            .checked_mul(elapsed)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(duration)
+            .ok_or(SafePumpError::MathError)? as u64)
+    }
+}
+
+#[account]
+pub struct Presale {
+    pub contract: Pubkey,
+    pub escrow: Pubkey, // Token account holding the presale allocation
+    pub merkle_root: [u8; 32], // Root over keccak(wallet, max_contribution) leaves
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub hard_cap: u64, // Max lamports raised
+    pub token_amount: u64, // Tokens shared pro rata between contributors
+    pub total_raised: u64,
+    pub finalized: bool,
+    pub finalize_timestamp: i64, // Vesting starts here
+    pub vesting_duration: i64, // Seconds of linear unlock after finalization, 0 unlocks at once
+    pub bump: u8,
+}
+
+impl Presale {
+    pub fn unlocked_allocation(&self, contributed: u64, now: i64) -> Result<u64> {
+        if self.total_raised == 0 {
+            return Ok(0);
+        }
+        let allocation = (self.token_amount as u128)
+            .checked_mul(contributed as u128)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(self.total_raised as u128)
+            .ok_or(SafePumpError::MathError)?;
+        let elapsed = now.saturating_sub(self.finalize_timestamp);
+        if self.vesting_duration == 0 || elapsed >= self.vesting_duration {
+            return Ok(allocation as u64);
+        }
+        Ok(allocation
+            .checked_mul(elapsed.max(0) as u128)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(self.vesting_duration as u128)
+            .ok_or(SafePumpError::MathError)? as u64)
+    }
+}
+
+#[account]
+pub struct PresaleReceipt {
+    pub presale: Pubkey,
+    pub wallet: Pubkey,
+    pub contributed: u64, // Lamports contributed
+    pub claimed: u64, // Tokens claimed
+    pub bump: u8,
+}
+
+#[account]
+pub struct LpLock {
+    pub contract: Pubkey,
+    pub owner: Pubkey, // Wallet allowed to extend and withdraw
+    pub lp_mint: Pubkey,
+    pub escrow: Pubkey, // LP token account owned by this lock
+    pub total_amount: u64,
+    pub withdrawn_amount: u64,
+    pub lock_timestamp: i64,
+    pub unlock_timestamp: i64, // Fully unlocked from this point
+    pub linear_release: bool, // Release linearly from lock_timestamp instead of all at unlock_timestamp
+    pub bump: u8,
+}
+
+impl LpLock {
+    pub fn unlocked_amount(&self, now: i64) -> Result<u64> {
+        if now >= self.unlock_timestamp {
+            return Ok(self.total_amount);
+        }
+        if !self.linear_release || now <= self.lock_timestamp {
+            return Ok(0);
+        }
+        let elapsed = (now - self.lock_timestamp) as u128;
+        let duration = (self.unlock_timestamp - self.lock_timestamp) as u128;
+        Ok((self.total_amount as u128)
+            .checked_mul(elapsed)
+            .ok_or(SafePumpError::MathError)?
+            .checked_div(duration)
+            .ok_or(SafePumpError::MathError)? as u64)
+    }
+}
+
+#[account]
+pub struct BadgeRegistry {
+    pub holder_count: u64, // Badge holders across all pages
+    pub page_count: u64, // Holder pages created so far
+    pub bump: u8,
+}
+
+// Seeded under "badge-page", apart from the single "badge-holders" account of older versions
+#[account]
+pub struct BadgeHolderPage {
+    pub page: u64,
+    pub holders: [Pubkey; BADGE_PAGE_SIZE],
+    pub tiers: [BadgeTier; BADGE_PAGE_SIZE], // Tier of the holder at the same index
+    pub holder_count: u64,
+    pub bump: u8,
+}
+
+impl BadgeHolderPage {
+    pub fn index_of(&self, holder: &Pubkey) -> Option<usize> {
+        self.holders[..self.holder_count as usize].iter().position(|h| h == holder)
+    }
+
+    pub fn insert(&mut self, holder: Pubkey, tier: BadgeTier) -> Result<()> {
+        require!(self.holder_count < BADGE_PAGE_SIZE as u64, SafePumpError::BadgeHolderLimitReached);
+        self.holders[self.holder_count as usize] = holder;
+        self.tiers[self.holder_count as usize] = tier;
+        self.holder_count += 1;
+        Ok(())
+    }
+
+    // Swap-removes the holder so the page stays dense, returning the tier they held
+    pub fn remove(&mut self, holder: &Pubkey) -> Result<BadgeTier> {
+        let index = self.index_of(holder).ok_or(SafePumpError::NotBadgeHolder)?;
+        let last = self.holder_count as usize - 1;
+        let tier = self.tiers[index];
+        self.holders[index] = self.holders[last];
+        self.tiers[index] = self.tiers[last];
+        self.holders[last] = Pubkey::default();
+        self.tiers[last] = BadgeTier::None;
+        self.holder_count -= 1;
+        Ok(tier)
+    }
+}
+
+#[account]
+pub struct BadgeProgress {
+    pub buy_swap_count: u64, // Buys counted towards badge tiers
+    pub is_holder: bool,
+    pub page: u64, // Registry page holding the badge while is_holder
+    pub sell_volume_at_award: u64, // UserSwapData.sell_volume when the badge was minted
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BadgeRevocation {
+    Sold, // Sold more than badge_max_sell_percent of holdings since the award
+    Inactive, // No trades for badge_inactivity_period
+    LowBalance, // Balance below badge_min_balance
+    MissingBadge, // Badge token burned or its account closed
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum BadgeTier {
+    None,
+    Bronze,
+    Silver,
+    Gold,
+    Diamond,
+}
+
+#[derive(Clone, Copy)]
+pub struct BadgeTierRequirement {
+    pub min_buy_count: u64,
+    pub min_buy_volume: u64, // Lamports
+    pub min_holding_duration: i64, // Seconds since the user's first trade
+    pub reward_weight: u64,
+}
+
+impl BadgeTier {
+    const ALL: [BadgeTier; BADGE_TIERS] = [BadgeTier::Bronze, BadgeTier::Silver, BadgeTier::Gold, BadgeTier::Diamond];
+
+    // Highest tier whose buy count, buy volume and holding duration thresholds are all met
+    pub fn qualified(badge_progress: &BadgeProgress, user_swap_data: &UserSwapData, now: i64) -> BadgeTier {
+        let holding_duration = if user_swap_data.first_trade_timestamp == 0 {
+            0
+        } else {
+            now.saturating_sub(user_swap_data.first_trade_timestamp)
+        };
+        Self::ALL
+            .iter()
+            .zip(BADGE_TIER_REQUIREMENTS.iter())
+            .filter(|(_, requirement)| {
+                badge_progress.buy_swap_count >= requirement.min_buy_count
+                    && user_swap_data.buy_volume >= requirement.min_buy_volume
+                    && holding_duration >= requirement.min_holding_duration
+            })
+            .map(|(tier, _)| *tier)
+            .last()
+            .unwrap_or(BadgeTier::None)
+    }
+
+    fn requirement(&self) -> Option<&'static BadgeTierRequirement> {
+        match self {
+            BadgeTier::None => None,
+            tier => BADGE_TIER_REQUIREMENTS.get(*tier as usize - 1),
+        }
+    }
+
+    pub fn reward_weight(&self) -> u64 {
+        self.requirement().map_or(0, |requirement| requirement.reward_weight)
+    }
+
+    pub fn badge_name(&self) -> &'static str {
+        match self {
+            BadgeTier::None => "SafePump Badge",
+            BadgeTier::Bronze => "SafePump Bronze Badge",
+            BadgeTier::Silver => "SafePump Silver Badge",
+            BadgeTier::Gold => "SafePump Gold Badge",
+            BadgeTier::Diamond => "SafePump Diamond Badge",
+        }
+    }
+}
+
+#[account]
+pub struct MemeCoinRegistry {
+    pub meme_coins: [(Pubkey, Pubkey); 1000],
+    pub meme_coin_count: u64,
+    pub bump: u8,
+}
+
+impl MemeCoinRegistry {
+    pub fn index_of(&self, program_id: Pubkey) -> Option<usize> {
+        self.meme_coins
+            .iter()
+            .take(self.meme_coin_count as usize)
+            .position(|(pid, _)| *pid == program_id)
+    }
+}
+
+// Tax schedule of one registered meme coin, kept out of MemeCoinRegistry so the registry layout never changes
+#[account]
+pub struct MemeCoinTax {
+    pub contract: Pubkey,
+    pub program_id: Pubkey,
+    pub schedule: TaxSchedule,
+    pub bump: u8,
+}
+
+// Per-coin swap tax in basis points, split the same way as the SafePump tax
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaxSchedule {
+    pub lp_tax: u16,
+    pub swapper_reward_tax: u16,
+    pub badge_reward_tax: u16,
+}
+
+impl TaxSchedule {
+    pub const PROTOCOL: TaxSchedule = TaxSchedule {
+        lp_tax: GLOBAL_LP_TAX as u16,
+        swapper_reward_tax: SWAPPER_REWARD_TAX as u16,
+        badge_reward_tax: BADGE_REWARD_TAX as u16,
+    };
+
+    pub fn total(&self) -> u64 {
+        self.lp_tax as u64 + self.swapper_reward_tax as u64 + self.badge_reward_tax as u64
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let total = self.total();
+        require!(
+            (MIN_MEME_COIN_TAX..=MAX_MEME_COIN_TAX).contains(&total),
+            SafePumpError::InvalidTaxSchedule
+        );
+        Ok(())
+    }
+
+    // Deployers may only lower a registered schedule, and every share must stay at or below its current value
+    pub fn check_lowered_from(&self, current: &TaxSchedule) -> Result<()> {
+        require!(
+            self.lp_tax <= current.lp_tax
+                && self.swapper_reward_tax <= current.swapper_reward_tax
+                && self.badge_reward_tax <= current.badge_reward_tax,
+            SafePumpError::TaxIncreaseNotAllowed
+        );
+        self.validate()
+    }
+
+    // SafePump sellers keep their own swapper share, only the LP and badge shares are taken in tokens
+    pub fn token_tax_rate(&self) -> u64 {
+        self.lp_tax as u64 + self.badge_reward_tax as u64
+    }
+
+    pub fn tax_on(&self, amount: u64) -> Result<u64> {
+        Ok(amount.checked_mul(self.total()).ok_or(SafePumpError::MathError)? / 10_000)
+    }
+
+    // Split the collected tax into (lp, swapper, badge) shares
+    pub fn split(&self, total_tax: u64) -> Result<(u64, u64, u64)> {
+        let total = self.total();
+        if total == 0 {
+            return Ok((0, 0, 0));
+        }
+        let share = |part: u16| -> Result<u64> {
+            Ok(total_tax.checked_mul(part as u64).ok_or(SafePumpError::MathError)? / total)
+        };
+        Ok((share(self.lp_tax)?, share(self.swapper_reward_tax)?, share(self.badge_reward_tax)?))
+    }
+}
+
+// Tax taken from one swap, shared by the swap instructions and quote_swap so previews match execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapCharges {
+    pub total_tax: u64, // Tax actually collected from the swapper
+    pub lp_tax: u64,
+    pub swapper_tax: u64,
+    pub badge_tax: u64,
+    pub swap_amount: u64, // Portion of an exact-in amount routed through the pool
+}
+
+impl SwapCharges {
+    // Tax on `amount` of swap input; SafePump sellers keep their own swapper share
+    pub fn on(schedule: TaxSchedule, amount: u64, sells_safepump: bool) -> Result<Self> {
+        let scheduled_tax = schedule.tax_on(amount)?;
+        let (lp_tax, swapper_tax, badge_tax) = schedule.split(scheduled_tax)?;
+        let (total_tax, swapper_tax) = if sells_safepump {
+            (scheduled_tax - swapper_tax, 0)
+        } else {
+            (scheduled_tax, swapper_tax)
+        };
+        Ok(SwapCharges { total_tax, lp_tax, swapper_tax, badge_tax, swap_amount: amount })
+    }
+
+    // SafePump sell tax is pulled from the wallet on top, every other swap pays it out of `amount`
+    pub fn exact_in(schedule: TaxSchedule, amount: u64, sells_safepump: bool) -> Result<Self> {
+        let mut charges = Self::on(schedule, amount, sells_safepump)?;
+        if !sells_safepump {
+            charges.swap_amount = amount.checked_sub(charges.total_tax).ok_or(SafePumpError::MathError)?;
+        }
+        Ok(charges)
+    }
+
+    // Tokens leaving a SafePump seller's wallet: the swap input plus the token and launch tax pulled on top.
+    // Sell allowance and sell_volume are both charged this gross amount
+    pub fn sell_debit(&self, amount_in: u64, launch_tax: u64) -> Result<u64> {
+        Ok(amount_in
+            .checked_add(self.total_tax)
+            .and_then(|debit| debit.checked_add(launch_tax))
+            .ok_or(SafePumpError::MathError)?)
+    }
+}
+
+#[account]
+pub struct SlotBuyTracker {
+    pub slot: u64, // Slot the counters below refer to
+    pub buy_count: u64,
+    pub buy_volume: u64, // Lamports bought in the current slot
+    pub recent_buys: [(u64, Pubkey); SNIPER_RING_SIZE], // Ring buffer of (slot, buyer)
+    pub head: u64, // Next ring buffer index to overwrite
+}
+
+impl SlotBuyTracker {
+    // One buy per wallet per slot, within the slot's buy count and volume limits
+    pub fn record_buy(&mut self, buyer: Pubkey, amount: u64, slot: u64, max_buys: u64, max_volume: u64) -> Result<()> {
+        if self.slot != slot {
+            self.slot = slot;
+            self.buy_count = 0;
+            self.buy_volume = 0;
+        }
+        require!(
+            !self.recent_buys.iter().any(|(buy_slot, recent_buyer)| *buy_slot == slot && *recent_buyer == buyer),
+            SafePumpError::WalletSlotBuyLimit
+        );
+        require!(self.buy_count < max_buys, SafePumpError::SlotBuyLimit);
+        self.buy_volume = self.buy_volume.checked_add(amount).ok_or(SafePumpError::MathError)?;
+        require!(self.buy_volume <= max_volume, SafePumpError::SlotBuyLimit);
+
+        let head = self.head as usize;
+        self.recent_buys[head] = (slot, buyer);
+        self.head = ((head + 1) % SNIPER_RING_SIZE) as u64;
+        self.buy_count += 1;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct UserSwapData {
+    pub last_sell_timestamp: i64,
+    pub bump: u8,
+    pub recent_sells: [(i64, u64); SELL_HISTORY_SIZE], // Ring buffer of (timestamp, amount)
+    pub sell_history_head: u64, // Oldest entry, overwritten by the next sell
+    pub buy_count: u64,
+    pub sell_count: u64,
+    pub buy_volume: u64, // Lamports spent on buys
+    pub sell_volume: u64, // Tokens sold
+    pub first_trade_timestamp: i64,
+    pub last_buy_timestamp: i64,
+    pub rewards_earned: u64, // Lamports of swapper rewards credited
+}
+
+impl UserSwapData {
+    // Tokens sold within the trailing window ending at `now`
+    pub fn sold_in_window(&self, window: i64, now: i64) -> u64 {
+        self.recent_sells
+            .iter()
+            .filter(|(timestamp, amount)| *amount > 0 && now - timestamp < window)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    // Remaining sell allowance, measured against the holdings at the start of the window
+    pub fn sell_allowance(&self, balance: u64, max_sell_percent: u64, window: i64, now: i64) -> u64 {
+        let sold = self.sold_in_window(window, now);
+        let window_balance = balance.saturating_add(sold) as u128;
+        let allowance = (window_balance * max_sell_percent as u128 / 10_000) as u64;
+        allowance.saturating_sub(sold)
+    }
+
+    // Seconds until the oldest sell still inside the window drops out of it
+    pub fn window_resets_in(&self, window: i64, now: i64) -> i64 {
+        self.recent_sells
+            .iter()
+            .filter(|(timestamp, amount)| *amount > 0 && now - timestamp < window)
+            .map(|(timestamp, _)| timestamp + window - now)
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn record_trade(&mut self, is_buy: bool, volume: u64, now: i64) -> Result<()> {
+        if self.first_trade_timestamp == 0 {
+            self.first_trade_timestamp = now;
+        }
+        if is_buy {
+            self.buy_count = self.buy_count.checked_add(1).ok_or(SafePumpError::MathError)?;
+            self.buy_volume = self.buy_volume.checked_add(volume).ok_or(SafePumpError::MathError)?;
+            self.last_buy_timestamp = now;
+        } else {
+            self.sell_count = self.sell_count.checked_add(1).ok_or(SafePumpError::MathError)?;
+            self.sell_volume = self.sell_volume.checked_add(volume).ok_or(SafePumpError::MathError)?;
+        }
+        Ok(())
+    }
+
+    fn record_sell(&mut self, amount: u64, window: i64, now: i64) -> Result<()> {
+        let head = self.sell_history_head as usize;
+        let (timestamp, previous) = self.recent_sells[head];
+        if previous > 0 && now - timestamp < window {
+            // History is full of in-window sells, so fold the oldest into the next oldest. It keeps
+            // counting until that entry leaves the window, which can only tighten the allowance
+            let next = (head + 1) % SELL_HISTORY_SIZE;
+            self.recent_sells[next].1 = self.recent_sells[next].1
+                .checked_add(previous)
+                .ok_or(SafePumpError::MathError)?;
+        }
+        self.recent_sells[head] = (now, amount);
+        self.sell_history_head = ((head + 1) % SELL_HISTORY_SIZE) as u64;
+        self.last_sell_timestamp = now;
+        Ok(())
+    }
+}
+
+// Seeded under "reward-accumulator" so pushed-distribution accounts of older versions are never read with this layout
+#[account]
+pub struct RewardDistribution {
+    pub acc_reward_per_share: u128, // Badge lamports per unit of weight, scaled by REWARD_PRECISION
+    pub total_weight: u64, // Sum of badge holders' reward weights
+    pub unallocated_badge_rewards: u64, // Badge tax accrued while no weight was registered, or forfeited
+    pub total_claimed: u64,
     pub bump: u8,
 }
 
-#[account]
-pub struct BadgeHolders {
-    pub holders: [Pubkey; MAX_BADGE_HOLDERS],
-    pub buy_swap_count: [(Pubkey, u64); MAX_BADGE_HOLDERS],
-    pub holder_count: u64,
-    pub bump: u8,
+impl RewardDistribution {
+    // Spreads badge tax over the current weight in O(1); holders collect it when they settle
+    pub fn accrue_badge_rewards(&mut self, badge_tax: u64) -> Result<()> {
+        if self.total_weight == 0 {
+            self.unallocated_badge_rewards = self.unallocated_badge_rewards
+                .checked_add(badge_tax)
+                .ok_or(SafePumpError::MathError)?;
+            return Ok(());
+        }
+        let amount = badge_tax
+            .checked_add(self.unallocated_badge_rewards)
+            .ok_or(SafePumpError::MathError)?;
+        self.unallocated_badge_rewards = 0;
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(amount as u128 * REWARD_PRECISION / self.total_weight as u128)
+            .ok_or(SafePumpError::MathError)?;
+        Ok(())
+    }
+
+    // Settles the user at their old weight before moving them to `weight`
+    pub fn set_weight(&mut self, user_rewards: &mut UserRewards, weight: u64) -> Result<()> {
+        user_rewards.settle(self.acc_reward_per_share)?;
+        self.total_weight = self
+            .total_weight
+            .checked_sub(user_rewards.weight)
+            .and_then(|total| total.checked_add(weight))
+            .ok_or(SafePumpError::MathError)?;
+        user_rewards.weight = weight;
+        user_rewards.reward_debt = user_rewards.accumulated(self.acc_reward_per_share)?;
+        Ok(())
+    }
 }
 
 #[account]
-pub struct MemeCoinRegistry {
-    pub meme_coins: [(Pubkey, Pubkey); 1000],
-    pub meme_coin_count: u64,
+pub struct UserRewards {
+    pub weight: u64, // Badge reward weight currently earning
+    pub reward_debt: u128, // Accumulated badge rewards already settled at the current weight
+    pub pending_rewards: u64, // Lamports claimable by claim_rewards
+    pub claimed_rewards: u64,
     pub bump: u8,
 }
 
-#[account]
-pub struct UserSwapData {
-    pub last_sell_timestamp: i64,
-    pub bump: u8,
-}
+impl UserRewards {
+    fn accumulated(&self, acc_reward_per_share: u128) -> Result<u128> {
+        Ok((self.weight as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(SafePumpError::MathError)?
+            / REWARD_PRECISION)
+    }
 
-#[account]
-pub struct RewardDistribution {
-    pub swapper_rewards: [(Pubkey, u64); MAX_BADGE_HOLDERS],
-    pub badge_rewards: u64,
-    pub swap_count: u64,
-    pub last_distribution_timestamp: i64,
-    pub bump: u8,
+    // Moves badge rewards accrued since the last settlement into pending_rewards
+    pub fn settle(&mut self, acc_reward_per_share: u128) -> Result<u64> {
+        let accumulated = self.accumulated(acc_reward_per_share)?;
+        let accrued = accumulated
+            .checked_sub(self.reward_debt)
+            .ok_or(SafePumpError::MathError)? as u64;
+        self.reward_debt = accumulated;
+        self.pending_rewards = self.pending_rewards.checked_add(accrued).ok_or(SafePumpError::MathError)?;
+        Ok(accrued)
+    }
 }
 
 #[error_code]
@@ -981,8 +4583,18 @@ pub enum SafePumpError {
     InsufficientBuySwaps,
     #[msg("User is already a badge holder")]
     AlreadyBadgeHolder,
-    #[msg("Badge holder limit reached")]
+    #[msg("Badge holder page is full")]
     BadgeHolderLimitReached,
+    #[msg("Badge holder pages must be created in order")]
+    InvalidBadgePage,
+    #[msg("No rewards to claim")]
+    NoRewardsToClaim,
+    #[msg("Account is not a badge holder")]
+    NotBadgeHolder,
+    #[msg("Next badge tier requirements not met")]
+    BadgeTierNotReached,
+    #[msg("Badge holder still meets the badge maintenance rules")]
+    BadgeStillValid,
     #[msg("Meme coin already registered")]
     MemeCoinAlreadyRegistered,
     #[msg("Meme coin not registered")]
@@ -1003,8 +4615,1189 @@ pub enum SafePumpError {
     SellLockActive,
     #[msg("Invalid friends wallet allocation")]
     InvalidFriendsAllocation,
+    #[msg("Invalid launch config")]
+    InvalidLaunchConfig,
+    #[msg("Wallet is not a vesting beneficiary")]
+    NotVestingBeneficiary,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Invalid vesting escrow")]
+    InvalidVestingEscrow,
+    #[msg("Contract is not bonded to Raydium")]
+    NotBonded,
+    #[msg("Contract is already bonded to Raydium")]
+    AlreadyBonded,
+    #[msg("Bonding curve is disabled for this launch")]
+    CurveDisabled,
+    #[msg("Bonding curve is complete")]
+    CurveComplete,
+    #[msg("Bonding curve is not complete")]
+    CurveNotComplete,
+    #[msg("Insufficient bonding curve reserves")]
+    InsufficientCurveReserves,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Slippage exceeded")]
+    SlippageExceeded,
+    #[msg("Instruction called out of launch stage order")]
+    InvalidLaunchStage,
+    #[msg("Required launch account is missing")]
+    MissingLaunchAccount,
+    #[msg("Invalid token metadata")]
+    InvalidMetadata,
+    #[msg("Token metadata is frozen")]
+    MetadataFrozen,
+    #[msg("Invalid LP lock duration")]
+    InvalidLockDuration,
+    #[msg("Invalid LP lock escrow")]
+    InvalidLpLockEscrow,
+    #[msg("Invalid presale config")]
+    InvalidPresaleConfig,
+    #[msg("Presale is still active")]
+    PresaleActive,
+    #[msg("Presale is not open")]
+    PresaleNotOpen,
+    #[msg("Presale has not ended")]
+    PresaleNotEnded,
+    #[msg("Presale already finalized")]
+    PresaleFinalized,
+    #[msg("Invalid merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Exceeds max presale contribution")]
+    ExceedsMaxContribution,
+    #[msg("Presale hard cap reached")]
+    PresaleHardCapReached,
+    #[msg("Transaction deadline exceeded")]
+    DeadlineExceeded,
+    #[msg("Token tax vault does not match the contract")]
+    InvalidTokenTaxVault,
+    #[msg("Tax schedule outside protocol bounds")]
+    InvalidTaxSchedule,
+    #[msg("Meme coin tax rates can only be lowered")]
+    TaxIncreaseNotAllowed,
+    #[msg("Signer is not the meme coin deployer")]
+    NotMemeCoinDeployer,
+    #[msg("Treasury token account is not owned by the treasury wallet")]
+    InvalidTreasuryAccount,
+    #[msg("Per-slot buy limit reached")]
+    SlotBuyLimit,
+    #[msg("Wallet already bought in this slot")]
+    WalletSlotBuyLimit,
+    #[msg("Exceeds max wallet holding")]
+    ExceedsMaxWallet,
+    #[msg("Account already uses the current layout")]
+    AlreadyMigrated,
+    #[msg("Pool accounts do not match the bonded Raydium pool")]
+    InvalidPoolVault,
+    #[msg("Badge token account required while holding badge weight")]
+    BadgeAccountRequired,
+    #[msg("Badge token account is not the holder's badge ATA")]
+    InvalidBadgeAccount,
+    #[msg("AMM config does not match the pool")]
+    InvalidAmmConfig,
+    #[msg("LP mint is not the bonded pool's LP mint")]
+    InvalidLpMint,
+}
+
+fn validate_metadata(name: Option<&String>, symbol: Option<&String>, uri: Option<&String>) -> Result<()> {
+    if let Some(name) = name {
+        require!(!name.is_empty() && name.len() <= MAX_NAME_LENGTH, SafePumpError::InvalidMetadata);
+    }
+    if let Some(symbol) = symbol {
+        require!(!symbol.is_empty() && symbol.len() <= MAX_SYMBOL_LENGTH, SafePumpError::InvalidMetadata);
+    }
+    if let Some(uri) = uri {
+        require!(uri.len() <= MAX_URI_LENGTH, SafePumpError::InvalidMetadata);
+    }
+    Ok(())
+}
+
+// Sorted-pair keccak merkle proof, matching the allowlist tree built off-chain
+fn verify_merkle_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let mut computed = leaf;
+    for node in proof {
+        computed = if computed <= *node {
+            keccak::hashv(&[&computed, node]).to_bytes()
+        } else {
+            keccak::hashv(&[node, &computed]).to_bytes()
+        };
+    }
+    computed == root
+}
+
+// Raydium CPMM output after its trade fee, rounded down
+fn constant_product_amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, trade_fee_rate: u64) -> Result<u64> {
+    let fee = (amount_in as u128)
+        .checked_mul(trade_fee_rate as u128)
+        .ok_or(SafePumpError::MathError)?
+        .checked_add(RAYDIUM_FEE_RATE_DENOMINATOR as u128 - 1)
+        .ok_or(SafePumpError::MathError)?
+        / RAYDIUM_FEE_RATE_DENOMINATOR as u128;
+    let amount_in_after_fee = (amount_in as u128).saturating_sub(fee);
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in_after_fee)
+        .ok_or(SafePumpError::MathError)?;
+    if denominator == 0 {
+        return Ok(0);
+    }
+    Ok((amount_in_after_fee
+        .checked_mul(reserve_out as u128)
+        .ok_or(SafePumpError::MathError)?
+        / denominator) as u64)
+}
+
+// Sell lock, max sell and cooldown rules shared by global_tax_swap and the transfer hook
+fn check_sell_limits(
+    contract: &TokenContract,
+    config: &LaunchConfig,
+    user_swap_data: &mut UserSwapData,
+    amount: u64,
+    balance: u64,
+    now: i64,
+) -> Result<()> {
+    require!(!contract.sell_lock_active, SafePumpError::SellLockActive);
+    let allowance = user_swap_data.sell_allowance(balance, config.max_sell_percent, config.sell_cooldown, now);
+    require!(amount <= allowance, SafePumpError::ExceedsMaxSell);
+    user_swap_data.record_sell(amount, config.sell_cooldown, now)
+}
+
+// Tax schedule of a swap: the protocol rate for SafePump, the registered rate for meme coins
+fn swap_tax_schedule(
+    contract_key: &Pubkey,
+    is_safepump_swap: bool,
+    meme_program_id: Pubkey,
+    registry: &MemeCoinRegistry,
+    meme_coin_tax: Option<&MemeCoinTax>,
+) -> Result<TaxSchedule> {
+    if is_safepump_swap {
+        return Ok(TaxSchedule::PROTOCOL);
+    }
+    require!(registry.index_of(meme_program_id).is_some(), SafePumpError::MemeCoinNotRegistered);
+    let meme_coin_tax = meme_coin_tax.ok_or(SafePumpError::MemeCoinNotRegistered)?;
+    require!(
+        meme_coin_tax.contract == *contract_key && meme_coin_tax.program_id == meme_program_id,
+        SafePumpError::MemeCoinNotRegistered
+    );
+    Ok(meme_coin_tax.schedule)
+}
+
+// Meme coin swaps route through their own pool, SafePump swaps only through the bonded one
+fn check_swap_pool(
+    contract: &TokenContract,
+    is_safepump_swap: bool,
+    pool_state: &Pubkey,
+    vault: &Pubkey,
+    sol_vault: &Pubkey,
+) -> Result<()> {
+    let bonded_pool = *pool_state == contract.pool_state;
+    if is_safepump_swap {
+        require!(
+            bonded_pool && *vault == contract.pool_vault && *sol_vault == contract.pool_sol_vault,
+            SafePumpError::InvalidPoolVault
+        );
+    } else {
+        require!(!bonded_pool, SafePumpError::InvalidPoolVault);
+    }
+    Ok(())
+}
+
+fn check_max_wallet(
+    contract_key: &Pubkey,
+    contract: &TokenContract,
+    config: &LaunchConfig,
+    token_account: &Pubkey,
+    holder: &Pubkey,
+    balance_after: u64,
+) -> Result<()> {
+    if contract.is_max_wallet_exempt(contract_key, token_account, holder) {
+        return Ok(());
+    }
+    let max_wallet_amount = config.max_wallet_amount(contract.total_supply, contract.liquidity_threshold_index)?;
+    require!(balance_after <= max_wallet_amount, SafePumpError::ExceedsMaxWallet);
+    Ok(())
+}
+
+// SafePump mints carry a transfer hook, so their transfers forward the hook's extra accounts
+fn transfer_safepump<'info>(ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>, amount: u64, decimals: u8) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
+
+// Rewrites badge metadata fields; Token-2022 reallocates for longer values, so rent is topped up first
+fn update_badge_metadata<'info>(
+    token_program: &AccountInfo<'info>,
+    system: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    badge_mint: &AccountInfo<'info>,
+    contract: &AccountInfo<'info>,
+    contract_seeds: &[&[u8]],
+    fields: Vec<(Field, String)>,
+) -> Result<()> {
+    let added_len: usize = fields.iter().map(|(_, value)| value.len()).sum();
+    let required_lamports = Rent::get()?.minimum_balance(badge_mint.data_len() + added_len);
+    let extra_lamports = required_lamports.saturating_sub(badge_mint.lamports());
+    if extra_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: badge_mint.clone(),
+                },
+            ),
+            extra_lamports,
+        )?;
+    }
+    for (field, value) in fields {
+        token_interface::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    program_id: token_program.clone(),
+                    metadata: badge_mint.clone(),
+                    update_authority: contract.clone(),
+                },
+                &[contract_seeds],
+            ),
+            field,
+            value,
+        )?;
+    }
+    Ok(())
+}
+
+// A holder's badge counts only while their account holds the single token of their derived badge mint
+// Any other account is rejected rather than read as a missing badge
+fn holds_badge(account: &AccountInfo, holder: &Pubkey, safepump_mint: &Pubkey) -> Result<bool> {
+    let (badge_mint, _) = Pubkey::find_program_address(
+        &[b"badge-mint", safepump_mint.as_ref(), holder.as_ref()],
+        &crate::ID,
+    );
+    require!(
+        account.key() == get_associated_token_address_with_program_id(holder, &badge_mint, &spl_token_2022::ID),
+        SafePumpError::InvalidBadgeAccount
+    );
+    if account.data_is_empty() {
+        return Ok(false);
+    }
+    let badge_token_account = InterfaceAccount::<TokenAccount>::try_from(account)?;
+    Ok(badge_token_account.owner == *holder && badge_token_account.amount == 1)
+}
+
+// Balance of a token account that may have been closed
+fn token_balance(account: &AccountInfo) -> Result<u64> {
+    if account.data_is_empty() {
+        return Ok(0);
+    }
+    Ok(InterfaceAccount::<TokenAccount>::try_from(account)?.amount)
+}
+
+fn basis_points(amount: u64, bps: u64) -> Result<u64> {
+    Ok(((amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(SafePumpError::MathError)?
+        / 10_000) as u64)
 }
 
-fn get_associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
-    anchor_spl::associated_token::get_associated_token_address(owner, mint)
+fn vesting_allocation(amount: u64, burn_percentage: u8) -> Result<u64> {
+    Ok(amount
+        .checked_mul(100 - burn_percentage as u64)
+        .ok_or(SafePumpError::MathError)?
+        / 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixtures::*;
+
+    // Shared account fixtures, each test overrides only the fields it exercises
+    mod fixtures {
+        use super::*;
+
+        pub fn curve(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> BondingCurve {
+            BondingCurve {
+                contract: Pubkey::default(),
+                vault: Pubkey::default(),
+                sol_vault: Pubkey::default(),
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                real_sol_reserves: 0,
+                real_token_reserves: virtual_token_reserves,
+                sol_target: 0,
+                complete: false,
+                migrated: false,
+                bump: 0,
+            }
+        }
+
+        pub fn launch_config(decay: LaunchTaxDecay) -> LaunchConfig {
+            LaunchConfig {
+                authority: Pubkey::default(),
+                anti_sniper_cooldown: 0,
+                max_sell_percent: 1_000,
+                sell_cooldown: 3_600,
+                pool_sol_amount: 0,
+                initial_vault_amount: 0,
+                max_allocation_percent: 0,
+                liquidity_thresholds: [0; LIQUIDITY_TIERS],
+                buy_cap_percentages: [0; LIQUIDITY_TIERS],
+                market_cap_threshold_start: 0,
+                market_cap_threshold_end: 0,
+                buy_cap_start: 0,
+                buy_cap_end: 0,
+                sell_unlock_buy_cap: 0,
+                vesting_cliff: 0,
+                vesting_duration: 0,
+                curve_sol_target: 0,
+                curve_virtual_sol_reserves: 0,
+                curve_supply_percent: 0,
+                launch_tax_start: 1_000,
+                launch_tax_duration: 400,
+                launch_tax_half_life: 100,
+                launch_tax_decay: decay,
+                launch_tax_destination: LaunchTaxDestination::Burn,
+                sniper_guard_slots: 0,
+                max_buys_per_slot: 0,
+                max_buy_volume_per_slot: 0,
+                max_wallet_percentages: [0; LIQUIDITY_TIERS],
+                badge_max_sell_percent: 0,
+                badge_inactivity_period: 0,
+                badge_min_balance: 0,
+                bump: 0,
+            }
+        }
+
+        pub fn token_contract() -> TokenContract {
+            TokenContract {
+                is_initialized: true,
+                total_supply: 1_000_000_000,
+                treasury_wallet: Pubkey::new_unique(),
+                swap_count: 0,
+                total_swapped: 0,
+                vault_sol_balance: 0,
+                vault_token_balance: 0,
+                burned_tokens: 0,
+                burn_percentage: 0,
+                bond_timestamp: 0,
+                buy_cap_percentage: 10,
+                sell_lock_active: false,
+                liquidity_threshold_index: 0,
+                friends_wallets: [Pubkey::default(); MAX_FRIENDS_WALLETS],
+                friends_amounts: [0; MAX_FRIENDS_WALLETS],
+                deployer_amount: 0,
+                launch_config: Pubkey::new_unique(),
+                vesting_escrow: Pubkey::new_unique(),
+                vested_tokens: 0,
+                mint: Pubkey::new_unique(),
+                launch_stage: LaunchStage::Bonded,
+                metadata_frozen: false,
+                mint_renounced: false,
+                freeze_renounced: false,
+                presale_active: false,
+                token_tax_vault: Pubkey::new_unique(),
+                token_tax_balance: 0,
+                token_tax_converted: 0,
+                bond_slot: 0,
+                pool_vault: Pubkey::new_unique(),
+                pool_sol_vault: Pubkey::new_unique(),
+                pool_state: Pubkey::new_unique(),
+                bump: 255,
+            }
+        }
+
+        pub fn launch_config_params() -> LaunchConfigParams {
+            LaunchConfigParams {
+                anti_sniper_cooldown: 60,
+                max_sell_percent: 500,
+                sell_cooldown: 3_600,
+                pool_sol_amount: LAMPORTS_PER_SOL,
+                initial_vault_amount: 1_000_000_000,
+                max_allocation_percent: 1_000,
+                liquidity_thresholds: [0, 10, 20, 30, 40].map(|sol| sol * LAMPORTS_PER_SOL),
+                buy_cap_percentages: [10, 20, 30, 40, 50],
+                market_cap_threshold_start: 100 * LAMPORTS_PER_SOL,
+                market_cap_threshold_end: 1_000 * LAMPORTS_PER_SOL,
+                buy_cap_start: 50,
+                buy_cap_end: 100,
+                sell_unlock_buy_cap: 25,
+                vesting_cliff: 0,
+                vesting_duration: 86_400,
+                curve_sol_target: 0,
+                curve_virtual_sol_reserves: 0,
+                curve_supply_percent: 0,
+                launch_tax_start: 0,
+                launch_tax_duration: 0,
+                launch_tax_half_life: 0,
+                launch_tax_decay: LaunchTaxDecay::Linear,
+                launch_tax_destination: LaunchTaxDestination::Burn,
+                sniper_guard_slots: 0,
+                max_buys_per_slot: 0,
+                max_buy_volume_per_slot: 0,
+                max_wallet_percentages: [100, 200, 300, 400, 500],
+                badge_max_sell_percent: 1_000,
+                badge_inactivity_period: 86_400,
+                badge_min_balance: 0,
+            }
+        }
+
+        pub fn user_swap_data() -> UserSwapData {
+            UserSwapData {
+                last_sell_timestamp: 0,
+                bump: 0,
+                recent_sells: [(0, 0); SELL_HISTORY_SIZE],
+                sell_history_head: 0,
+                buy_count: 0,
+                sell_count: 0,
+                buy_volume: 0,
+                sell_volume: 0,
+                first_trade_timestamp: 0,
+                last_buy_timestamp: 0,
+                rewards_earned: 0,
+            }
+        }
+
+        pub fn reward_distribution() -> RewardDistribution {
+            RewardDistribution {
+                acc_reward_per_share: 0,
+                total_weight: 0,
+                unallocated_badge_rewards: 0,
+                total_claimed: 0,
+                bump: 0,
+            }
+        }
+
+        pub fn user_rewards() -> UserRewards {
+            UserRewards { weight: 0, reward_debt: 0, pending_rewards: 0, claimed_rewards: 0, bump: 0 }
+        }
+
+        pub fn badge_holder_page() -> BadgeHolderPage {
+            BadgeHolderPage {
+                page: 0,
+                holders: [Pubkey::default(); BADGE_PAGE_SIZE],
+                tiers: [BadgeTier::None; BADGE_PAGE_SIZE],
+                holder_count: 0,
+                bump: 0,
+            }
+        }
+
+        pub fn badge_progress(buy_swap_count: u64) -> BadgeProgress {
+            BadgeProgress { buy_swap_count, is_holder: false, page: 0, sell_volume_at_award: 0, bump: 255 }
+        }
+
+        pub fn slot_buy_tracker() -> SlotBuyTracker {
+            SlotBuyTracker {
+                slot: 0,
+                buy_count: 0,
+                buy_volume: 0,
+                recent_buys: [(0, Pubkey::default()); SNIPER_RING_SIZE],
+                head: 0,
+            }
+        }
+
+        pub fn lp_lock(linear_release: bool) -> LpLock {
+            LpLock {
+                contract: Pubkey::new_unique(),
+                owner: Pubkey::new_unique(),
+                lp_mint: Pubkey::new_unique(),
+                escrow: Pubkey::new_unique(),
+                total_amount: 1_000_000,
+                withdrawn_amount: 0,
+                lock_timestamp: 1_000,
+                unlock_timestamp: 11_000,
+                linear_release,
+                bump: 255,
+            }
+        }
+    }
+
+    #[test]
+    fn curve_buy_follows_constant_product() {
+        let curve = curve(100, 1_000);
+        assert_eq!(curve.tokens_out_for_sol(100).unwrap(), 500);
+        assert_eq!(curve.tokens_out_for_sol(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn curve_rounds_in_its_own_favour() {
+        let curve = curve(100, 1_000);
+        // Exact output is 9.9 tokens
+        assert_eq!(curve.tokens_out_for_sol(1).unwrap(), 9);
+        // Exact output is 9.09 lamports
+        assert_eq!(curve.sol_out_for_tokens(100).unwrap(), 9);
+    }
+
+    #[test]
+    fn curve_round_trip_never_profits() {
+        let mut curve = curve(30 * LAMPORTS_PER_SOL, 1_073_000_000 * 10u64.pow(TOKEN_DECIMALS as u32));
+        let sol_in = 3 * LAMPORTS_PER_SOL + 7;
+        let tokens = curve.tokens_out_for_sol(sol_in).unwrap();
+        curve.virtual_sol_reserves += sol_in;
+        curve.virtual_token_reserves -= tokens;
+        assert!(curve.sol_out_for_tokens(tokens).unwrap() <= sol_in);
+    }
+
+    #[test]
+    fn curve_sell_cannot_drain_sol_reserves() {
+        let curve = curve(100, 1_000);
+        assert!(curve.sol_out_for_tokens(u64::MAX).unwrap() < 100);
+    }
+
+    fn merkle_leaf(wallet: &Pubkey, max_contribution: u64) -> [u8; 32] {
+        keccak::hashv(&[wallet.as_ref(), &max_contribution.to_le_bytes()]).to_bytes()
+    }
+
+    fn merkle_parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).to_bytes()
+        } else {
+            keccak::hashv(&[&b, &a]).to_bytes()
+        }
+    }
+
+    #[test]
+    fn merkle_proof_accepts_allowlisted_leaves() {
+        let leaves: Vec<[u8; 32]> = (0..4u64).map(|i| merkle_leaf(&Pubkey::new_unique(), i * LAMPORTS_PER_SOL)).collect();
+        let left = merkle_parent(leaves[0], leaves[1]);
+        let right = merkle_parent(leaves[2], leaves[3]);
+        let root = merkle_parent(left, right);
+
+        assert!(verify_merkle_proof(&[leaves[1], right], root, leaves[0]));
+        assert!(verify_merkle_proof(&[leaves[2], left], root, leaves[3]));
+    }
+
+    #[test]
+    fn merkle_proof_rejects_wrong_leaf_or_proof() {
+        let wallet = Pubkey::new_unique();
+        let leaf = merkle_leaf(&wallet, LAMPORTS_PER_SOL);
+        let sibling = merkle_leaf(&Pubkey::new_unique(), LAMPORTS_PER_SOL);
+        let root = merkle_parent(leaf, sibling);
+
+        // A raised max contribution changes the leaf
+        assert!(!verify_merkle_proof(&[sibling], root, merkle_leaf(&wallet, 2 * LAMPORTS_PER_SOL)));
+        assert!(!verify_merkle_proof(&[], root, leaf));
+        assert!(!verify_merkle_proof(&[leaf], root, leaf));
+    }
+
+    #[test]
+    fn vesting_allocation_applies_burn() {
+        assert_eq!(vesting_allocation(1_000, 0).unwrap(), 1_000);
+        assert_eq!(vesting_allocation(1_000, 25).unwrap(), 750);
+        assert_eq!(vesting_allocation(999, 50).unwrap(), 499);
+        assert!(vesting_allocation(u64::MAX, 10).is_err());
+    }
+
+    #[test]
+    fn vesting_unlocks_linearly_after_cliff() {
+        let schedule = VestingSchedule {
+            contract: Pubkey::default(),
+            beneficiary: Pubkey::default(),
+            total_amount: 1_000,
+            claimed_amount: 0,
+            start_timestamp: 100,
+            cliff_timestamp: 200,
+            end_timestamp: 500,
+            bump: 0,
+        };
+        assert_eq!(schedule.vested_amount(199).unwrap(), 0);
+        assert_eq!(schedule.vested_amount(200).unwrap(), 250);
+        assert_eq!(schedule.vested_amount(300).unwrap(), 500);
+        assert_eq!(schedule.vested_amount(500).unwrap(), 1_000);
+        assert_eq!(schedule.vested_amount(10_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn presale_allocation_is_pro_rata_and_vested() {
+        let presale = Presale {
+            contract: Pubkey::default(),
+            escrow: Pubkey::default(),
+            merkle_root: [0; 32],
+            start_timestamp: 0,
+            end_timestamp: 100,
+            hard_cap: 10 * LAMPORTS_PER_SOL,
+            token_amount: 1_000_000,
+            total_raised: 4 * LAMPORTS_PER_SOL,
+            finalized: true,
+            finalize_timestamp: 1_000,
+            vesting_duration: 100,
+            bump: 0,
+        };
+        assert_eq!(presale.unlocked_allocation(LAMPORTS_PER_SOL, 1_000).unwrap(), 0);
+        assert_eq!(presale.unlocked_allocation(LAMPORTS_PER_SOL, 1_050).unwrap(), 125_000);
+        assert_eq!(presale.unlocked_allocation(LAMPORTS_PER_SOL, 1_100).unwrap(), 250_000);
+    }
+
+    #[test]
+    fn buy_charges_route_amount_minus_tax() {
+        let charges = SwapCharges::exact_in(TaxSchedule::PROTOCOL, 100_000, false).unwrap();
+        assert_eq!(charges.total_tax, 1_000);
+        assert_eq!((charges.lp_tax, charges.swapper_tax, charges.badge_tax), (500, 400, 100));
+        assert_eq!(charges.swap_amount, 99_000);
+    }
+
+    #[test]
+    fn sell_charges_keep_swapper_share_and_route_full_amount() {
+        let charges = SwapCharges::exact_in(TaxSchedule::PROTOCOL, 100_000, true).unwrap();
+        assert_eq!(charges.total_tax, 600);
+        assert_eq!((charges.lp_tax, charges.swapper_tax, charges.badge_tax), (500, 0, 100));
+        assert_eq!(charges.swap_amount, 100_000);
+    }
+
+    #[test]
+    fn linear_launch_tax_falls_to_zero_at_duration() {
+        let config = launch_config(LaunchTaxDecay::Linear);
+        assert_eq!(config.launch_tax_rate(1_000, 1_000), 1_000);
+        assert_eq!(config.launch_tax_rate(1_000, 1_100), 750);
+        assert_eq!(config.launch_tax_rate(1_000, 1_200), 500);
+        assert_eq!(config.launch_tax_rate(1_000, 1_399), 2);
+        assert_eq!(config.launch_tax_rate(1_000, 1_400), 0);
+    }
+
+    #[test]
+    fn exponential_launch_tax_halves_each_half_life() {
+        let config = launch_config(LaunchTaxDecay::Exponential);
+        assert_eq!(config.launch_tax_rate(1_000, 1_000), 1_000);
+        assert_eq!(config.launch_tax_rate(1_000, 1_050), 750);
+        assert_eq!(config.launch_tax_rate(1_000, 1_100), 500);
+        assert_eq!(config.launch_tax_rate(1_000, 1_200), 250);
+        assert_eq!(config.launch_tax_rate(1_000, 1_300), 125);
+        assert_eq!(config.launch_tax_rate(1_000, 1_400), 0);
+    }
+
+    #[test]
+    fn launch_tax_is_off_before_bonding_or_when_disabled() {
+        let mut config = launch_config(LaunchTaxDecay::Linear);
+        assert_eq!(config.launch_tax_rate(0, 1_000), 0);
+        config.launch_tax_start = 0;
+        assert_eq!(config.launch_tax_rate(1_000, 1_000), 0);
+    }
+
+    #[test]
+    fn sell_allowance_rolls_with_the_window() {
+        let mut data = user_swap_data();
+        assert_eq!(data.sell_allowance(1_000, 1_000, 3_600, 100), 100);
+
+        data.record_sell(60, 3_600, 100).unwrap();
+        // Measured against the 1_000 held when the window opened, not the 940 left
+        assert_eq!(data.sell_allowance(940, 1_000, 3_600, 200), 40);
+        assert_eq!(data.window_resets_in(3_600, 200), 3_500);
+
+        assert_eq!(data.sell_allowance(940, 1_000, 3_600, 3_700), 94);
+        assert_eq!(data.window_resets_in(3_600, 3_700), 0);
+    }
+
+    #[test]
+    fn full_sell_history_merges_instead_of_rejecting() {
+        let mut data = user_swap_data();
+        for timestamp in 0..=SELL_HISTORY_SIZE as i64 {
+            data.record_sell(1, 3_600, timestamp).unwrap();
+        }
+        assert_eq!(data.sold_in_window(3_600, SELL_HISTORY_SIZE as i64), SELL_HISTORY_SIZE as u64 + 1);
+        // The first sell now leaves the window with the second, never before it
+        assert_eq!(data.sold_in_window(3_600, 3_600), SELL_HISTORY_SIZE as u64 + 1);
+        assert_eq!(data.sold_in_window(3_600, 3_601), SELL_HISTORY_SIZE as u64 - 1);
+    }
+
+    #[test]
+    fn badge_rewards_wait_for_weight() {
+        let mut distribution = reward_distribution();
+        distribution.accrue_badge_rewards(100).unwrap();
+        assert_eq!(distribution.unallocated_badge_rewards, 100);
+        assert_eq!(distribution.acc_reward_per_share, 0);
+
+        let mut holder = user_rewards();
+        distribution.set_weight(&mut holder, 1).unwrap();
+        distribution.accrue_badge_rewards(50).unwrap();
+        assert_eq!(distribution.unallocated_badge_rewards, 0);
+        assert_eq!(holder.settle(distribution.acc_reward_per_share).unwrap(), 150);
+        assert_eq!(holder.pending_rewards, 150);
+        assert_eq!(holder.settle(distribution.acc_reward_per_share).unwrap(), 0);
+    }
+
+    #[test]
+    fn badge_rewards_split_by_weight_from_join_time() {
+        let mut distribution = reward_distribution();
+        let (mut early, mut late) = (user_rewards(), user_rewards());
+        distribution.set_weight(&mut early, 1).unwrap();
+        distribution.accrue_badge_rewards(100).unwrap();
+
+        distribution.set_weight(&mut late, 3).unwrap();
+        distribution.accrue_badge_rewards(400).unwrap();
+
+        assert_eq!(early.settle(distribution.acc_reward_per_share).unwrap(), 200);
+        assert_eq!(late.settle(distribution.acc_reward_per_share).unwrap(), 300);
+    }
+
+    #[test]
+    fn set_weight_settles_at_the_old_weight() {
+        let mut distribution = reward_distribution();
+        let mut holder = user_rewards();
+        distribution.set_weight(&mut holder, 2).unwrap();
+        distribution.accrue_badge_rewards(100).unwrap();
+
+        distribution.set_weight(&mut holder, 0).unwrap();
+        assert_eq!(holder.pending_rewards, 100);
+        assert_eq!(distribution.total_weight, 0);
+
+        distribution.accrue_badge_rewards(100).unwrap();
+        assert_eq!(holder.settle(distribution.acc_reward_per_share).unwrap(), 0);
+        assert_eq!(distribution.unallocated_badge_rewards, 100);
+    }
+
+    #[test]
+    fn revoked_badge_holder_can_be_added_again() {
+        let mut page = badge_holder_page();
+        let (revoked, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        page.insert(revoked, BadgeTier::Bronze).unwrap();
+        page.insert(other, BadgeTier::Silver).unwrap();
+
+        assert_eq!(page.remove(&revoked).unwrap(), BadgeTier::Bronze);
+        assert_eq!(page.index_of(&other), Some(0));
+        assert_eq!(page.holder_count, 1);
+        assert!(page.remove(&revoked).is_err());
+
+        page.insert(revoked, BadgeTier::Gold).unwrap();
+        assert_eq!(page.index_of(&revoked), Some(1));
+        assert_eq!(page.tiers[1], BadgeTier::Gold);
+    }
+
+    #[test]
+    fn sell_volume_matches_the_allowance_charge() {
+        let charges = SwapCharges::exact_in(TaxSchedule::PROTOCOL, 10_000, true).unwrap();
+        let launch_tax = basis_points(10_000, 100).unwrap();
+        let debit = charges.sell_debit(10_000, launch_tax).unwrap();
+        assert_eq!(debit, 10_000 + 60 + 100);
+
+        let mut data = user_swap_data();
+        data.record_sell(debit, 3_600, 100).unwrap();
+        data.record_trade(false, debit, 100).unwrap();
+        assert_eq!(data.sold_in_window(3_600, 100), data.sell_volume);
+        assert_eq!(data.sell_allowance(100_000 - debit, 2_000, 3_600, 100), 20_000 - debit);
+    }
+
+    #[test]
+    fn badge_revocation_boundaries() {
+        let mut config = launch_config(LaunchTaxDecay::Linear);
+        config.badge_max_sell_percent = 1_000;
+        config.badge_inactivity_period = 100;
+        config.badge_min_balance = 500;
+        let mut data = user_swap_data();
+        data.first_trade_timestamp = 50;
+        data.sell_volume = 300;
+
+        // Exactly 10% of the 1_000 held at award is still allowed, one more token is not
+        assert_eq!(config.badge_revocation(&data, 200, 900, 100).unwrap(), None);
+        assert_eq!(config.badge_revocation(&data, 199, 899, 100).unwrap(), Some(BadgeRevocation::Sold));
+
+        assert_eq!(config.badge_revocation(&data, 300, 900, 149).unwrap(), None);
+        assert_eq!(config.badge_revocation(&data, 300, 900, 150).unwrap(), Some(BadgeRevocation::Inactive));
+
+        assert_eq!(config.badge_revocation(&data, 300, 500, 100).unwrap(), None);
+        assert_eq!(config.badge_revocation(&data, 300, 499, 100).unwrap(), Some(BadgeRevocation::LowBalance));
+    }
+
+    #[test]
+    fn badge_revocation_counts_sell_tax() {
+        let mut config = launch_config(LaunchTaxDecay::Linear);
+        config.badge_max_sell_percent = 1_000;
+        let charges = SwapCharges::exact_in(TaxSchedule::PROTOCOL, 100, true).unwrap();
+        let debit = charges.sell_debit(100, 1).unwrap();
+
+        // A net sale of exactly 10% goes over once its tax is counted
+        let mut data = user_swap_data();
+        data.record_trade(false, debit, 100).unwrap();
+        assert_eq!(config.badge_revocation(&data, 0, 1_000 - debit, 100).unwrap(), Some(BadgeRevocation::Sold));
+    }
+
+    #[test]
+    fn migrated_pool_opens_at_the_curve_closing_price() {
+        let mut curve = curve(30 * LAMPORTS_PER_SOL, 1_000_000_000);
+        for sol_in in [5 * LAMPORTS_PER_SOL, 20 * LAMPORTS_PER_SOL, 60 * LAMPORTS_PER_SOL] {
+            let tokens_out = curve.tokens_out_for_sol(sol_in).unwrap();
+            curve.virtual_sol_reserves += sol_in;
+            curve.virtual_token_reserves -= tokens_out;
+            curve.real_sol_reserves += sol_in;
+        }
+
+        let pool_sol = curve.real_sol_reserves;
+        let pool_tokens = curve.pool_tokens_for_sol(pool_sol).unwrap();
+        // pool_sol / pool_tokens == virtual_sol / virtual_tokens, up to one token of rounding in the pool's favour
+        let pool_side = pool_sol as u128 * curve.virtual_token_reserves as u128;
+        let curve_side = pool_tokens as u128 * curve.virtual_sol_reserves as u128;
+        assert!(pool_side >= curve_side);
+        assert!(pool_side - curve_side < curve.virtual_sol_reserves as u128);
+        assert!(pool_tokens < curve.virtual_token_reserves);
+    }
+
+    #[test]
+    fn max_wallet_caps_holders_at_the_tier_limit() {
+        let contract_key = Pubkey::new_unique();
+        let contract = token_contract();
+        let mut config = launch_config(LaunchTaxDecay::Linear);
+        config.max_wallet_percentages = [100, 200, 300, 400, 500];
+        let (ata, holder) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // 100 bp of 1B supply
+        assert!(check_max_wallet(&contract_key, &contract, &config, &ata, &holder, 9_999_999).is_ok());
+        assert!(check_max_wallet(&contract_key, &contract, &config, &ata, &holder, 10_000_000).is_ok());
+        assert!(check_max_wallet(&contract_key, &contract, &config, &ata, &holder, 10_000_001).is_err());
+    }
+
+    #[test]
+    fn max_wallet_exempts_protocol_accounts() {
+        let contract_key = Pubkey::new_unique();
+        let contract = token_contract();
+        let config = launch_config(LaunchTaxDecay::Linear);
+        let holder = Pubkey::new_unique();
+        let over_cap = contract.total_supply;
+
+        assert!(check_max_wallet(&contract_key, &contract, &config, &Pubkey::new_unique(), &contract_key, over_cap).is_ok());
+        assert!(check_max_wallet(&contract_key, &contract, &config, &contract.pool_vault, &holder, over_cap).is_ok());
+        assert!(check_max_wallet(&contract_key, &contract, &config, &contract.pool_sol_vault, &holder, over_cap).is_ok());
+        assert!(check_max_wallet(&contract_key, &contract, &config, &Pubkey::new_unique(), &holder, over_cap).is_err());
+    }
+
+    #[test]
+    fn meme_coin_swaps_use_their_registered_tax() {
+        let contract_key = Pubkey::new_unique();
+        let meme_program_id = Pubkey::new_unique();
+        let mut registry = MemeCoinRegistry {
+            meme_coins: [(Pubkey::default(), Pubkey::default()); 1000],
+            meme_coin_count: 1,
+            bump: 255,
+        };
+        registry.meme_coins[0] = (meme_program_id, Pubkey::new_unique());
+        let schedule = TaxSchedule { lp_tax: 50, swapper_reward_tax: 20, badge_reward_tax: 10 };
+        let meme_coin_tax = MemeCoinTax { contract: contract_key, program_id: meme_program_id, schedule, bump: 255 };
+
+        let quoted = swap_tax_schedule(&contract_key, false, meme_program_id, &registry, Some(&meme_coin_tax)).unwrap();
+        assert_eq!(quoted, schedule);
+        let protocol = swap_tax_schedule(&contract_key, true, Pubkey::new_unique(), &registry, None).unwrap();
+        assert_eq!(protocol, TaxSchedule::PROTOCOL);
+
+        assert!(swap_tax_schedule(&contract_key, false, meme_program_id, &registry, None).is_err());
+        assert!(swap_tax_schedule(&contract_key, false, Pubkey::new_unique(), &registry, Some(&meme_coin_tax)).is_err());
+        assert!(swap_tax_schedule(&Pubkey::new_unique(), false, meme_program_id, &registry, Some(&meme_coin_tax)).is_err());
+    }
+
+    #[test]
+    fn safepump_swaps_only_use_the_bonded_pool() {
+        let contract = token_contract();
+        let other = Pubkey::new_unique();
+        let (pool, vault, sol_vault) = (contract.pool_state, contract.pool_vault, contract.pool_sol_vault);
+
+        assert!(check_swap_pool(&contract, true, &pool, &vault, &sol_vault).is_ok());
+        assert!(check_swap_pool(&contract, true, &pool, &other, &sol_vault).is_err());
+        assert!(check_swap_pool(&contract, true, &pool, &vault, &other).is_err());
+        assert!(check_swap_pool(&contract, true, &other, &vault, &sol_vault).is_err());
+        assert!(check_swap_pool(&contract, false, &other, &other, &other).is_ok());
+        assert!(check_swap_pool(&contract, false, &pool, &vault, &sol_vault).is_err());
+    }
+
+    fn rejects(change: impl FnOnce(&mut LaunchConfigParams)) -> bool {
+        let mut params = launch_config_params();
+        change(&mut params);
+        params.validate().is_err()
+    }
+
+    #[test]
+    fn launch_config_params_accept_the_baseline() {
+        assert!(launch_config_params().validate().is_ok());
+    }
+
+    #[test]
+    fn launch_config_params_reject_out_of_range_bps() {
+        assert!(rejects(|p| p.max_sell_percent = 0));
+        assert!(rejects(|p| p.max_sell_percent = MAX_CAP_BPS + 1));
+        assert!(rejects(|p| p.buy_cap_percentages[0] = 0));
+        assert!(rejects(|p| p.buy_cap_end = MAX_CAP_BPS + 1));
+        assert!(rejects(|p| p.sell_unlock_buy_cap = 0));
+        assert!(rejects(|p| p.sell_unlock_buy_cap = p.buy_cap_end + 1));
+        assert!(rejects(|p| p.max_wallet_percentages = [100, 200, 300, 400, MAX_CAP_BPS + 1]));
+        assert!(rejects(|p| p.max_wallet_percentages[0] = p.buy_cap_percentages[0] - 1));
+        assert!(rejects(|p| p.launch_tax_start = MAX_LAUNCH_TAX + 1));
+        assert!(rejects(|p| p.badge_max_sell_percent = 0));
+        assert!(rejects(|p| p.badge_max_sell_percent = MAX_CAP_BPS + 1));
+    }
+
+    #[test]
+    fn launch_config_params_reject_unordered_tiers() {
+        assert!(rejects(|p| p.liquidity_thresholds[2] = p.liquidity_thresholds[1]));
+        assert!(rejects(|p| p.buy_cap_percentages[3] = p.buy_cap_percentages[2] - 1));
+        assert!(rejects(|p| p.max_wallet_percentages[4] = p.max_wallet_percentages[3] - 1));
+        assert!(rejects(|p| p.buy_cap_start = p.buy_cap_percentages[LIQUIDITY_TIERS - 1] - 1));
+        assert!(rejects(|p| p.market_cap_threshold_end = p.market_cap_threshold_start));
+    }
+
+    #[test]
+    fn launch_config_params_reject_inconsistent_supply_split() {
+        assert!(rejects(|p| p.initial_vault_amount = MAX_SUPPLY + 1));
+        assert!(rejects(|p| p.max_allocation_percent = MAX_ALLOCATION_PERCENT + 1));
+        assert!(rejects(|p| p.pool_sol_amount = MIN_POOL_SOL_AMOUNT - 1));
+        let curve_launch = |p: &mut LaunchConfigParams| {
+            p.curve_sol_target = 85 * LAMPORTS_PER_SOL;
+            p.curve_virtual_sol_reserves = 30 * LAMPORTS_PER_SOL;
+            p.curve_supply_percent = 8_000;
+        };
+        assert!(!rejects(curve_launch));
+        assert!(rejects(|p| {
+            curve_launch(p);
+            p.curve_supply_percent = MAX_CAP_BPS;
+        }));
+        assert!(rejects(|p| {
+            curve_launch(p);
+            p.curve_supply_percent = 0;
+        }));
+        assert!(rejects(|p| {
+            curve_launch(p);
+            p.curve_virtual_sol_reserves = MIN_POOL_SOL_AMOUNT - 1;
+        }));
+    }
+
+    #[test]
+    fn launch_config_params_reject_out_of_range_durations() {
+        assert!(rejects(|p| p.anti_sniper_cooldown = MAX_ANTI_SNIPER_COOLDOWN + 1));
+        assert!(rejects(|p| p.sell_cooldown = -1));
+        assert!(rejects(|p| p.vesting_duration = 0));
+        assert!(rejects(|p| p.vesting_cliff = p.vesting_duration + 1));
+        assert!(rejects(|p| {
+            p.launch_tax_start = 500;
+            p.launch_tax_duration = 0;
+        }));
+        assert!(rejects(|p| {
+            p.launch_tax_start = 500;
+            p.launch_tax_duration = 3_600;
+            p.launch_tax_decay = LaunchTaxDecay::Exponential;
+            p.launch_tax_half_life = 3_601;
+        }));
+        assert!(rejects(|p| p.sniper_guard_slots = MAX_SNIPER_GUARD_SLOTS + 1));
+        assert!(rejects(|p| {
+            p.sniper_guard_slots = 10;
+            p.max_buys_per_slot = SNIPER_RING_SIZE as u64 + 1;
+            p.max_buy_volume_per_slot = LAMPORTS_PER_SOL;
+        }));
+        assert!(rejects(|p| p.badge_inactivity_period = MAX_BADGE_INACTIVITY_PERIOD + 1));
+    }
+
+    #[test]
+    fn cliff_lp_lock_releases_everything_at_unlock() {
+        let lock = lp_lock(false);
+        assert_eq!(lock.unlocked_amount(500).unwrap(), 0);
+        assert_eq!(lock.unlocked_amount(6_000).unwrap(), 0);
+        assert_eq!(lock.unlocked_amount(10_999).unwrap(), 0);
+        assert_eq!(lock.unlocked_amount(11_000).unwrap(), 1_000_000);
+        assert_eq!(lock.unlocked_amount(50_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn linear_lp_lock_vests_between_lock_and_unlock() {
+        let lock = lp_lock(true);
+        assert_eq!(lock.unlocked_amount(500).unwrap(), 0);
+        assert_eq!(lock.unlocked_amount(1_000).unwrap(), 0);
+        assert_eq!(lock.unlocked_amount(3_500).unwrap(), 250_000);
+        assert_eq!(lock.unlocked_amount(6_000).unwrap(), 500_000);
+        assert_eq!(lock.unlocked_amount(10_999).unwrap(), 999_900);
+        assert_eq!(lock.unlocked_amount(11_000).unwrap(), 1_000_000);
+        assert_eq!(lock.unlocked_amount(50_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn tax_schedule_total_must_stay_in_range() {
+        let schedule = |lp_tax, swapper_reward_tax, badge_reward_tax| TaxSchedule { lp_tax, swapper_reward_tax, badge_reward_tax };
+        assert!(schedule(10, 10, 5).validate().is_ok());
+        assert!(schedule(300, 100, 100).validate().is_ok());
+        assert!(schedule(10, 10, 4).validate().is_err());
+        assert!(schedule(300, 100, 101).validate().is_err());
+    }
+
+    #[test]
+    fn tax_schedule_can_only_be_lowered() {
+        let current = TaxSchedule { lp_tax: 200, swapper_reward_tax: 100, badge_reward_tax: 100 };
+        let lowered = TaxSchedule { lp_tax: 150, swapper_reward_tax: 100, badge_reward_tax: 50 };
+        assert!(lowered.check_lowered_from(&current).is_ok());
+        assert!(current.check_lowered_from(&current).is_ok());
+
+        // A lower total still cannot raise any single share
+        let shifted = TaxSchedule { lp_tax: 250, swapper_reward_tax: 0, badge_reward_tax: 0 };
+        assert!(shifted.check_lowered_from(&current).is_err());
+        let raised = TaxSchedule { badge_reward_tax: 101, ..current };
+        assert!(raised.check_lowered_from(&current).is_err());
+
+        // Lowering below the minimum total is rejected too
+        let too_low = TaxSchedule { lp_tax: 10, swapper_reward_tax: 10, badge_reward_tax: 0 };
+        assert!(too_low.check_lowered_from(&current).is_err());
+    }
+
+    #[test]
+    fn slot_tracker_allows_one_buy_per_wallet_per_slot() {
+        let mut tracker = slot_buy_tracker();
+        let buyer = Pubkey::new_unique();
+        tracker.record_buy(buyer, 100, 10, 5, 1_000).unwrap();
+        assert!(tracker.record_buy(buyer, 100, 10, 5, 1_000).is_err());
+        tracker.record_buy(Pubkey::new_unique(), 100, 10, 5, 1_000).unwrap();
+        assert_eq!((tracker.buy_count, tracker.buy_volume), (2, 200));
+
+        // The next slot resets the counters and lets the same wallet buy again
+        tracker.record_buy(buyer, 100, 11, 5, 1_000).unwrap();
+        assert_eq!((tracker.slot, tracker.buy_count, tracker.buy_volume), (11, 1, 100));
+    }
+
+    #[test]
+    fn slot_tracker_limits_are_inclusive() {
+        let mut tracker = slot_buy_tracker();
+        for _ in 0..3 {
+            tracker.record_buy(Pubkey::new_unique(), 100, 10, 3, 1_000).unwrap();
+        }
+        assert!(tracker.record_buy(Pubkey::new_unique(), 100, 10, 3, 1_000).is_err());
+
+        let mut tracker = slot_buy_tracker();
+        tracker.record_buy(Pubkey::new_unique(), 600, 10, 3, 1_000).unwrap();
+        tracker.record_buy(Pubkey::new_unique(), 400, 10, 3, 1_000).unwrap();
+        assert_eq!(tracker.buy_volume, 1_000);
+        assert!(tracker.record_buy(Pubkey::new_unique(), 1, 10, 3, 1_000).is_err());
+    }
+
+    #[test]
+    fn slot_tracker_ring_evicts_the_oldest_buy() {
+        let mut tracker = slot_buy_tracker();
+        let first = Pubkey::new_unique();
+        tracker.record_buy(first, 1, 10, 1, 1_000).unwrap();
+        for slot in 11..10 + SNIPER_RING_SIZE as u64 {
+            tracker.record_buy(Pubkey::new_unique(), 1, slot, 1, 1_000).unwrap();
+        }
+        assert_eq!(tracker.head, 0);
+        assert_eq!(tracker.recent_buys[0], (10, first));
+
+        let newest = Pubkey::new_unique();
+        tracker.record_buy(newest, 1, 100, 1, 1_000).unwrap();
+        assert_eq!(tracker.recent_buys[0], (100, newest));
+        assert_eq!(tracker.head, 1);
+    }
+
+    // Exactly meets `requirement`, measured at `now`
+    fn qualifying_trader(requirement: &BadgeTierRequirement, now: i64) -> (BadgeProgress, UserSwapData) {
+        let mut data = user_swap_data();
+        data.buy_volume = requirement.min_buy_volume;
+        data.first_trade_timestamp = now - requirement.min_holding_duration;
+        (badge_progress(requirement.min_buy_count), data)
+    }
+
+    #[test]
+    fn badge_tiers_qualify_exactly_at_their_thresholds() {
+        let now = 1_000_000_000;
+        for (tier, requirement) in BadgeTier::ALL.iter().zip(BADGE_TIER_REQUIREMENTS.iter()) {
+            let (progress, data) = qualifying_trader(requirement, now);
+            assert_eq!(BadgeTier::qualified(&progress, &data, now), *tier);
+        }
+    }
+
+    #[test]
+    fn badge_tiers_need_every_threshold() {
+        let now = 1_000_000_000;
+        let bronze = &BADGE_TIER_REQUIREMENTS[0];
+        let silver = &BADGE_TIER_REQUIREMENTS[1];
+
+        let (mut progress, data) = qualifying_trader(silver, now);
+        progress.buy_swap_count -= 1;
+        assert_eq!(BadgeTier::qualified(&progress, &data, now), BadgeTier::Bronze);
+
+        let (progress, mut data) = qualifying_trader(silver, now);
+        data.buy_volume -= 1;
+        assert_eq!(BadgeTier::qualified(&progress, &data, now), BadgeTier::Bronze);
+
+        let (progress, data) = qualifying_trader(silver, now);
+        assert_eq!(BadgeTier::qualified(&progress, &data, now - 1), BadgeTier::Bronze);
+
+        let (mut progress, data) = qualifying_trader(bronze, now);
+        progress.buy_swap_count -= 1;
+        assert_eq!(BadgeTier::qualified(&progress, &data, now), BadgeTier::None);
+    }
+
+    #[test]
+    fn badge_tiers_need_a_first_trade() {
+        let (progress, mut data) = qualifying_trader(&BADGE_TIER_REQUIREMENTS[BADGE_TIERS - 1], 1_000_000_000);
+        data.first_trade_timestamp = 0;
+        assert_eq!(BadgeTier::qualified(&progress, &data, 1_000_000_000), BadgeTier::None);
+    }
+
+    #[test]
+    fn badge_rewards_track_weight_changes_across_accruals() {
+        let mut distribution = reward_distribution();
+        let (mut a, mut b, mut c) = (user_rewards(), user_rewards(), user_rewards());
+
+        distribution.set_weight(&mut a, 1).unwrap();
+        distribution.accrue_badge_rewards(100).unwrap(); // a: 100
+        distribution.set_weight(&mut b, 4).unwrap();
+        distribution.accrue_badge_rewards(500).unwrap(); // a: 100, b: 400
+        distribution.set_weight(&mut a, 2).unwrap();
+        distribution.set_weight(&mut c, 2).unwrap();
+        distribution.accrue_badge_rewards(800).unwrap(); // a: 200, b: 400, c: 200
+        distribution.set_weight(&mut b, 0).unwrap();
+        distribution.accrue_badge_rewards(400).unwrap(); // a: 200, c: 200
+        assert_eq!(distribution.total_weight, 4);
+
+        let acc = distribution.acc_reward_per_share;
+        let paid = [a.settle(acc).unwrap(), b.settle(acc).unwrap(), c.settle(acc).unwrap()];
+        assert_eq!([a.pending_rewards, b.pending_rewards, c.pending_rewards], [600, 800, 400]);
+        assert_eq!(paid, [400, 0, 400]);
+        assert_eq!(a.pending_rewards + b.pending_rewards + c.pending_rewards, 1_800);
+        assert_eq!(distribution.unallocated_badge_rewards, 0);
+    }
+
+    #[test]
+    fn badge_rewards_round_down_and_never_overpay() {
+        let mut distribution = reward_distribution();
+        let (mut a, mut b, mut c) = (user_rewards(), user_rewards(), user_rewards());
+        for holder in [&mut a, &mut b, &mut c] {
+            distribution.set_weight(holder, 1).unwrap();
+        }
+        for _ in 0..10 {
+            distribution.accrue_badge_rewards(100).unwrap();
+        }
+        let acc = distribution.acc_reward_per_share;
+        let total: u64 = [&mut a, &mut b, &mut c].into_iter().map(|holder| holder.settle(acc).unwrap()).sum();
+        assert!(total <= 1_000 && total >= 997);
+    }
+
+    #[test]
+    fn badge_reward_math_errors_instead_of_wrapping() {
+        let mut distribution = reward_distribution();
+        distribution.unallocated_badge_rewards = u64::MAX;
+        assert_eq!(distribution.accrue_badge_rewards(1).unwrap_err(), SafePumpError::MathError.into());
+
+        // A holder whose weight was never registered cannot be removed from the total
+        let mut distribution = reward_distribution();
+        let mut holder = user_rewards();
+        holder.weight = 1;
+        assert_eq!(distribution.set_weight(&mut holder, 0).unwrap_err(), SafePumpError::MathError.into());
+
+        // Settling against an older accumulator would pay negative rewards
+        let mut holder = user_rewards();
+        holder.weight = 1;
+        holder.reward_debt = 10;
+        assert_eq!(holder.settle(0).unwrap_err(), SafePumpError::MathError.into());
+    }
+
+    #[test]
+    fn badge_page_rejects_holders_past_capacity() {
+        let mut page = badge_holder_page();
+        for _ in 0..BADGE_PAGE_SIZE {
+            page.insert(Pubkey::new_unique(), BadgeTier::Bronze).unwrap();
+        }
+        assert_eq!(
+            page.insert(Pubkey::new_unique(), BadgeTier::Bronze).unwrap_err(),
+            SafePumpError::BadgeHolderLimitReached.into()
+        );
+        assert_eq!(page.remove(&Pubkey::new_unique()).unwrap_err(), SafePumpError::NotBadgeHolder.into());
+    }
 }